@@ -1,8 +1,13 @@
-use archon::channels::*;
-use bytes::Bytes;
+use archon::{
+    builder::ChannelConfig,
+    channels::*,
+    state::State,
+    test_utils::mock_chain,
+};
 use criterion::{
     criterion_group,
     criterion_main,
+    BatchSize,
     Criterion,
 };
 
@@ -16,16 +21,32 @@ criterion_group! {
         bench_channel_tx_data,
 }
 
-/// Benchmark message passing between the [Archon] client and the [ChannelManager].
+/// Benchmark building the first frame of a channel with [ChannelManager::tx_data].
+///
+/// The blocks are loaded from an in-memory [State], so no network is needed.
 pub fn bench_channel_tx_data(c: &mut Criterion) {
-    // let client = harness::mock_archon_client().unwrap();
-    // let channel_manager = ChannelManager::new();
-    let block_id = harness::await_future(harness::fetch_latest_block_id()).unwrap();
+    let block_id = harness::l1_block_at(100);
+    let mut state = State::new();
+    for block in mock_chain(1, 10, 500) {
+        state.add_block(block);
+    }
     c.bench_function("tx_data", |b| {
-        b.to_async(harness::construct_runtime()).iter(|| async {
-            let (tx_bytes, tx_id) = ChannelManager::tx_data(block_id).unwrap();
-            assert_eq!(tx_bytes, Bytes::new());
-            assert_eq!(tx_id, TransactionID::default());
-        })
+        b.iter_batched(
+            || {
+                let mut channel_manager = ChannelManager::new();
+                channel_manager.with_channel_config(ChannelConfig {
+                    target_frame_size: 1_000,
+                    ..ChannelConfig::default()
+                });
+                *channel_manager.state().lock().unwrap() = state.clone();
+                channel_manager
+            },
+            |mut channel_manager| {
+                let (tx_bytes, tx_id) = channel_manager.tx_data(block_id).unwrap();
+                assert!(!tx_bytes.is_empty());
+                assert_ne!(tx_id, TransactionID::default());
+            },
+            BatchSize::SmallInput,
+        )
     });
 }
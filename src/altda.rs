@@ -0,0 +1,69 @@
+use bytes::Bytes;
+use eyre::Result;
+
+use crate::{
+    builder::DERIVATION_VERSION_1,
+    errors::AltDaError,
+};
+
+/// Alt-DA Client
+///
+/// Stores frame data on a generic alt-DA server over HTTP. The server answers a `PUT /put`
+/// of the data with its commitment, and only the commitment is posted to the batch inbox.
+#[derive(Debug, Clone)]
+pub struct DaClient {
+    /// The url of the alt-DA server
+    url: String,
+    /// The HTTP client
+    client: reqwest::Client,
+}
+
+impl DaClient {
+    /// Constructs a new [DaClient] for the alt-DA server at the given url
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Returns the url of the alt-DA server
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Stores the data on the alt-DA server, returning its encoded commitment.
+    pub async fn set_input(&self, data: &[u8]) -> Result<Bytes> {
+        let response = self
+            .client
+            .put(format!("{}/put", self.url))
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(data.to_vec())
+            .send()
+            .await
+            .map_err(|e| AltDaError::Request(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(AltDaError::UnexpectedStatus(status.as_u16()).into())
+        }
+        let commitment = response
+            .bytes()
+            .await
+            .map_err(|e| AltDaError::Request(e.to_string()))?;
+        if commitment.is_empty() {
+            return Err(AltDaError::EmptyCommitment.into())
+        }
+        tracing::debug!(target: "archon::altda", "Stored {} bytes on the alt-DA server with commitment 0x{}", data.len(), hex::encode(&commitment));
+        Ok(commitment)
+    }
+}
+
+/// Returns the batcher transaction data posting the given alt-DA commitment.
+///
+/// The resulting bytes are the [DERIVATION_VERSION_1] byte followed by the encoded commitment.
+pub fn commitment_tx_data(commitment: &[u8]) -> Bytes {
+    let mut out = Vec::with_capacity(commitment.len() + 1);
+    out.push(DERIVATION_VERSION_1);
+    out.extend_from_slice(commitment);
+    Bytes::from(out)
+}
@@ -0,0 +1,314 @@
+//! Batch
+//!
+//! Encapsulates the conversion of L2 blocks into batches for the derivation pipeline.
+
+use ethers_core::{
+    types::{
+        Address,
+        Block,
+        Bytes,
+        Transaction,
+        H256,
+        U256,
+        U64,
+    },
+    utils::rlp::{
+        self,
+        Decodable,
+        DecoderError,
+        Encodable,
+        Rlp,
+        RlpStream,
+    },
+};
+use eyre::Result;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::errors::BatchError;
+
+/// The EIP-2718 transaction type of deposit transactions.
+pub const DEPOSIT_TX_TYPE: u64 = 0x7E;
+
+/// The EIP-2718 transaction type of EIP-7702 set code transactions.
+pub const SET_CODE_TX_TYPE: u64 = 0x04;
+
+/// The batch type byte of a [SingularBatch].
+pub const SINGULAR_BATCH_TYPE: u8 = 0x00;
+
+/// The function selector of the Bedrock `setL1BlockValues` L1 info deposit.
+pub const L1_INFO_BEDROCK_SELECTOR: [u8; 4] = [0x01, 0x5d, 0x8e, 0xb9];
+
+/// The function selector of the Ecotone `setL1BlockValuesEcotone` L1 info deposit.
+pub const L1_INFO_ECOTONE_SELECTOR: [u8; 4] = [0x44, 0x0a, 0x5e, 0x20];
+
+/// The function selector of the Isthmus `setL1BlockValuesIsthmus` L1 info deposit.
+pub const L1_INFO_ISTHMUS_SELECTOR: [u8; 4] = [0x09, 0x89, 0x99, 0xbe];
+
+/// The L1 block attributes carried by the L1 info deposit at the start of every L2 block.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct L1BlockInfo {
+    /// The L1 origin block number.
+    pub number: u64,
+    /// The L1 origin block timestamp.
+    pub timestamp: u64,
+    /// The L1 origin block hash.
+    pub hash: H256,
+    /// The position of the L2 block within its epoch.
+    pub sequence_number: u64,
+}
+
+impl L1BlockInfo {
+    /// Parses the [L1BlockInfo] from the calldata of an L1 info deposit transaction.
+    ///
+    /// The Bedrock (ABI-encoded), Ecotone (packed) and Isthmus (packed) formats are supported.
+    pub fn from_deposit_input(input: &[u8]) -> Result<Self> {
+        let selector = input.get(..4).ok_or(BatchError::InvalidL1InfoDeposit)?;
+        if selector == L1_INFO_BEDROCK_SELECTOR {
+            // Eight 32-byte ABI words follow the selector.
+            if input.len() != 4 + 8 * 32 {
+                return Err(BatchError::InvalidL1InfoDeposit.into())
+            }
+            let word = |i: usize| &input[4 + i * 32..4 + (i + 1) * 32];
+            Ok(Self {
+                number: be_u64(&word(0)[24..]),
+                timestamp: be_u64(&word(1)[24..]),
+                hash: H256::from_slice(word(3)),
+                sequence_number: be_u64(&word(4)[24..]),
+            })
+        } else if selector == L1_INFO_ECOTONE_SELECTOR
+            || selector == L1_INFO_ISTHMUS_SELECTOR
+        {
+            // baseFeeScalar (4) ++ blobBaseFeeScalar (4) ++ sequenceNumber (8) ++ timestamp (8)
+            // ++ number (8) ++ baseFee (32) ++ blobBaseFee (32) ++ hash (32) ++ batcherHash (32)
+            // Isthmus appends operatorFeeScalar (4) ++ operatorFeeConstant (8).
+            let expected_len = match selector == L1_INFO_ISTHMUS_SELECTOR {
+                true => 176,
+                false => 164,
+            };
+            if input.len() != expected_len {
+                return Err(BatchError::InvalidL1InfoDeposit.into())
+            }
+            Ok(Self {
+                sequence_number: be_u64(&input[12..20]),
+                timestamp: be_u64(&input[20..28]),
+                number: be_u64(&input[28..36]),
+                hash: H256::from_slice(&input[100..132]),
+            })
+        } else {
+            Err(BatchError::InvalidL1InfoDeposit.into())
+        }
+    }
+
+    /// Extracts the [L1BlockInfo] from the L1 info deposit of an L2 block.
+    ///
+    /// The L1 info deposit is always the first transaction in the block.
+    pub fn from_block(block: &Block<Transaction>) -> Result<Self> {
+        let deposit = block
+            .transactions
+            .first()
+            .filter(|tx| is_deposit(tx))
+            .ok_or(BatchError::MissingL1InfoDeposit)?;
+        Self::from_deposit_input(&deposit.input)
+    }
+}
+
+/// A singular batch holds the non-deposit transactions of a single L2 block.
+///
+/// In a channel, a singular batch is encoded as an RLP string of
+/// `SINGULAR_BATCH_TYPE ++ rlp([parent_hash, epoch_num, epoch_hash, timestamp, transactions])`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct SingularBatch {
+    /// The parent hash of the L2 block.
+    pub parent_hash: H256,
+    /// The L1 origin block number of the L2 block.
+    pub epoch_num: u64,
+    /// The L1 origin block hash of the L2 block.
+    pub epoch_hash: H256,
+    /// The L2 block timestamp.
+    pub timestamp: u64,
+    /// The EIP-2718 encoded non-deposit transactions of the L2 block.
+    pub transactions: Vec<Bytes>,
+}
+
+impl SingularBatch {
+    /// Constructs a [SingularBatch] from an L2 block.
+    ///
+    /// Deposit transactions are filtered out since they are derived from L1.
+    /// The epoch is taken from the L1 info deposit of the block.
+    pub fn from_block(block: &Block<Transaction>) -> Result<Self> {
+        let l1_info = L1BlockInfo::from_block(block)?;
+        let transactions = block
+            .transactions
+            .iter()
+            .filter(|tx| !is_deposit(tx))
+            .map(encode_transaction)
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            parent_hash: block.parent_hash,
+            epoch_num: l1_info.number,
+            epoch_hash: l1_info.hash,
+            timestamp: block.timestamp.as_u64(),
+            transactions,
+        })
+    }
+
+    /// Encodes the batch as typed batch data: `SINGULAR_BATCH_TYPE ++ rlp(batch)`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![SINGULAR_BATCH_TYPE];
+        out.extend_from_slice(&rlp::encode(self));
+        out
+    }
+
+    /// Decodes a batch from typed batch data.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        match data.split_first() {
+            Some((&SINGULAR_BATCH_TYPE, rest)) => {
+                rlp::decode(rest).map_err(|_| BatchError::InvalidBatch.into())
+            }
+            Some(_) => Err(BatchError::UnsupportedBatchType.into()),
+            None => Err(BatchError::InvalidBatch.into()),
+        }
+    }
+
+    /// Encodes the batch as it is written into a channel: an RLP string of the typed batch data.
+    pub fn rlp_bytes(&self) -> Vec<u8> {
+        rlp::encode(&self.encode()).to_vec()
+    }
+
+    /// Decodes a batch as it is read from a channel.
+    pub fn decode_rlp_bytes(data: &[u8]) -> Result<Self> {
+        let typed: Vec<u8> = rlp::decode(data).map_err(|_| BatchError::InvalidBatch)?;
+        Self::decode(&typed)
+    }
+}
+
+impl Encodable for SingularBatch {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(5);
+        s.append(&self.parent_hash);
+        s.append(&self.epoch_num);
+        s.append(&self.epoch_hash);
+        s.append(&self.timestamp);
+        s.begin_list(self.transactions.len());
+        for tx in &self.transactions {
+            s.append(&tx.as_ref());
+        }
+    }
+}
+
+impl Decodable for SingularBatch {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 5 {
+            return Err(DecoderError::RlpIncorrectListLen)
+        }
+        Ok(Self {
+            parent_hash: rlp.val_at(0)?,
+            epoch_num: rlp.val_at(1)?,
+            epoch_hash: rlp.val_at(2)?,
+            timestamp: rlp.val_at(3)?,
+            transactions: rlp
+                .list_at::<Vec<u8>>(4)?
+                .into_iter()
+                .map(Bytes::from)
+                .collect(),
+        })
+    }
+}
+
+/// An EIP-7702 authorization, as listed in the `authorizationList` of a set code transaction.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCodeAuthorization {
+    /// The chain id the authorization is valid on, or zero for any chain.
+    pub chain_id: U256,
+    /// The address whose code is delegated to.
+    pub address: Address,
+    /// The nonce of the authorizing account.
+    pub nonce: U64,
+    /// The signature y parity.
+    pub y_parity: U64,
+    /// The signature `r`.
+    pub r: U256,
+    /// The signature `s`.
+    pub s: U256,
+}
+
+impl Encodable for SetCodeAuthorization {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(6);
+        s.append(&self.chain_id);
+        s.append(&self.address);
+        s.append(&self.nonce);
+        s.append(&self.y_parity);
+        s.append(&self.r);
+        s.append(&self.s);
+    }
+}
+
+/// Encodes a [Transaction] in its EIP-2718 form, as it is included in batches.
+///
+/// ethers encodes any transaction type it does not know as a legacy transaction,
+/// so EIP-7702 set code transactions are encoded here and other types are rejected.
+pub fn encode_transaction(tx: &Transaction) -> Result<Bytes> {
+    match tx.transaction_type.map(|t| t.as_u64()) {
+        None | Some(0..=2) => Ok(tx.rlp()),
+        Some(SET_CODE_TX_TYPE) => encode_set_code_transaction(tx),
+        Some(t) => Err(BatchError::UnsupportedTransactionType(t).into()),
+    }
+}
+
+/// Encodes an EIP-7702 set code transaction as
+/// `0x04 ++ rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas, to, value,
+/// data, access_list, authorization_list, y_parity, r, s])`.
+fn encode_set_code_transaction(tx: &Transaction) -> Result<Bytes> {
+    let authorizations: Vec<SetCodeAuthorization> = tx
+        .other
+        .get_deserialized("authorizationList")
+        .and_then(|list| list.ok())
+        .ok_or(BatchError::InvalidTransaction)?;
+    let (Some(chain_id), Some(to), Some(max_priority_fee), Some(max_fee)) = (
+        tx.chain_id,
+        tx.to,
+        tx.max_priority_fee_per_gas,
+        tx.max_fee_per_gas,
+    ) else {
+        return Err(BatchError::InvalidTransaction.into())
+    };
+    let y_parity = match tx.v.as_u64() {
+        v @ 0..=1 => v,
+        _ => return Err(BatchError::InvalidTransaction.into()),
+    };
+
+    let mut s = RlpStream::new_list(13);
+    s.append(&chain_id);
+    s.append(&tx.nonce);
+    s.append(&max_priority_fee);
+    s.append(&max_fee);
+    s.append(&tx.gas);
+    s.append(&to);
+    s.append(&tx.value);
+    s.append(&tx.input.as_ref());
+    s.append(&tx.access_list.clone().unwrap_or_default());
+    s.append_list(&authorizations);
+    s.append(&y_parity);
+    s.append(&tx.r);
+    s.append(&tx.s);
+    let mut out = vec![SET_CODE_TX_TYPE as u8];
+    out.extend_from_slice(&s.out());
+    Ok(Bytes::from(out))
+}
+
+/// Returns whether the [Transaction] is a deposit transaction.
+pub fn is_deposit(tx: &Transaction) -> bool {
+    tx.transaction_type == Some(U64::from(DEPOSIT_TX_TYPE))
+}
+
+/// Reads a big-endian [u64] from an 8-byte slice.
+fn be_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_be_bytes(buf)
+}
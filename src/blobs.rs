@@ -0,0 +1,446 @@
+use std::fmt;
+
+use bytes::Bytes;
+use ethers_core::{
+    types::{
+        Address,
+        Signature,
+        H256,
+        U256,
+    },
+    utils::{
+        keccak256,
+        rlp::RlpStream,
+    },
+};
+use eyre::Result;
+use once_cell::sync::Lazy;
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+use crate::errors::BlobError;
+
+/// The Ethereum mainnet KZG trusted setup of the EIP-4844 ceremony, in the text format of c-kzg.
+///
+/// Vendored from c-kzg 2.1.8, sha256 `d39b9f2d047cc9dca2de58f264b6a09448ccd34db967881a6713eacacf0f26b7`.
+pub const TRUSTED_SETUP: &str = include_str!("trusted_setup.txt");
+
+/// The [TRUSTED_SETUP], loaded without precomputed tables on first use.
+static KZG_SETTINGS: Lazy<Result<c_kzg::KzgSettings, String>> = Lazy::new(|| {
+    c_kzg::KzgSettings::parse_kzg_trusted_setup(TRUSTED_SETUP, 0)
+        .map_err(|e| e.to_string())
+});
+
+/// The size of a blob, in bytes.
+pub const BLOB_SIZE: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+
+/// The number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+
+/// The size of a field element, in bytes.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+
+/// The number of rounds of the blob encoding. Each round packs 127 bytes into 4 field elements.
+pub const BLOB_ENCODING_ROUNDS: usize = FIELD_ELEMENTS_PER_BLOB / 4;
+
+/// The version of the blob encoding.
+pub const BLOB_ENCODING_VERSION: u8 = 0;
+
+/// The maximum size of the data encoded into a single blob, in bytes.
+pub const MAX_BLOB_DATA_SIZE: usize = (4 * 31 + 3) * BLOB_ENCODING_ROUNDS - 4;
+
+/// The size of a KZG commitment or proof, in bytes.
+pub const BYTES_PER_COMMITMENT: usize = 48;
+
+/// The version byte of the versioned hash of a KZG commitment.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The blob gas used by each blob of a transaction.
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+
+/// The EIP-2718 transaction type of a blob transaction.
+pub const BLOB_TX_TYPE: u8 = 0x03;
+
+/// The number of cells of an extended blob, each of which has a KZG proof since Fusaka.
+pub const CELLS_PER_EXT_BLOB: usize = 128;
+
+/// The compressed G1 point at infinity, which is the KZG commitment and proof of the zero blob.
+pub const G1_POINT_AT_INFINITY: [u8; BYTES_PER_COMMITMENT] = {
+    let mut point = [0u8; BYTES_PER_COMMITMENT];
+    point[0] = 0xc0;
+    point
+};
+
+/// A KZG commitment or proof.
+pub type Bytes48 = [u8; BYTES_PER_COMMITMENT];
+
+/// A blob of 4096 field elements, carrying frame data in the OP Stack blob encoding.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Blob(Vec<u8>);
+
+impl fmt::Debug for Blob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Blob({:?})", H256::from(keccak256(&self.0)))
+    }
+}
+
+impl Default for Blob {
+    fn default() -> Self {
+        Self(vec![0u8; BLOB_SIZE])
+    }
+}
+
+impl Blob {
+    /// Encodes the given data into a blob.
+    ///
+    /// The data is prefixed with the encoding version and its 3-byte length. Every 127 bytes are
+    /// then packed into 4 field elements: 31 bytes fill each field element after its first byte,
+    /// and the remaining 3 bytes are spread over the low 6 bits of the 4 first bytes, so that
+    /// every field element is smaller than the BLS modulus.
+    pub fn from_data(data: &[u8]) -> Result<Self, BlobError> {
+        if data.len() > MAX_BLOB_DATA_SIZE {
+            return Err(BlobError::DataTooLarge(data.len()))
+        }
+        let len = (data.len() as u32).to_be_bytes();
+        let mut input = Vec::with_capacity(MAX_BLOB_DATA_SIZE + 4);
+        input.extend_from_slice(&[BLOB_ENCODING_VERSION, len[1], len[2], len[3]]);
+        input.extend_from_slice(data);
+        input.resize(MAX_BLOB_DATA_SIZE + 4, 0);
+
+        let mut blob = vec![0u8; BLOB_SIZE];
+        for (chunk, output) in input.chunks(127).zip(blob.chunks_mut(128)) {
+            let (x, y, z) = (chunk[31], chunk[63], chunk[95]);
+            output[0] = x & 0b0011_1111;
+            output[1..32].copy_from_slice(&chunk[0..31]);
+            output[32] = (y & 0b0000_1111) | ((x & 0b1100_0000) >> 2);
+            output[33..64].copy_from_slice(&chunk[32..63]);
+            output[64] = z & 0b0011_1111;
+            output[65..96].copy_from_slice(&chunk[64..95]);
+            output[96] = ((z & 0b1100_0000) >> 2) | ((y & 0b1111_0000) >> 4);
+            output[97..128].copy_from_slice(&chunk[96..127]);
+        }
+        Ok(Self(blob))
+    }
+
+    /// Decodes the data encoded into the blob by [Blob::from_data].
+    pub fn to_data(&self) -> Result<Vec<u8>, BlobError> {
+        let mut output = Vec::with_capacity(MAX_BLOB_DATA_SIZE + 4);
+        for (index, round) in self.0.chunks(128).enumerate() {
+            let encoded = [round[0], round[32], round[64], round[96]];
+            if let Some(i) = encoded.iter().position(|b| b & 0b1100_0000 != 0) {
+                return Err(BlobError::InvalidFieldElement(index * 4 + i))
+            }
+            let x = (encoded[0] & 0b0011_1111) | ((encoded[1] & 0b0011_0000) << 2);
+            let y = (encoded[1] & 0b0000_1111) | ((encoded[3] & 0b0000_1111) << 4);
+            let z = (encoded[2] & 0b0011_1111) | ((encoded[3] & 0b0011_0000) << 2);
+            output.extend_from_slice(&round[1..32]);
+            output.push(x);
+            output.extend_from_slice(&round[33..64]);
+            output.push(y);
+            output.extend_from_slice(&round[65..96]);
+            output.push(z);
+            output.extend_from_slice(&round[97..128]);
+        }
+        if output[0] != BLOB_ENCODING_VERSION {
+            return Err(BlobError::UnsupportedEncodingVersion(output[0]))
+        }
+        let len = u32::from_be_bytes([0, output[1], output[2], output[3]]) as usize;
+        if len > MAX_BLOB_DATA_SIZE {
+            return Err(BlobError::InvalidLength(len))
+        }
+        let data = &output[4..];
+        if data[len..].iter().any(|b| *b != 0) {
+            return Err(BlobError::NonZeroPadding)
+        }
+        Ok(data[..len].to_vec())
+    }
+
+    /// Returns the raw bytes of the blob.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Computes the KZG commitments and proofs of blobs against the KZG trusted setup.
+pub trait KzgCommitter: fmt::Debug + Send + Sync {
+    /// Returns the KZG commitment of the blob.
+    fn blob_to_commitment(&self, blob: &Blob) -> Result<Bytes48>;
+
+    /// Returns the KZG proof of the blob against its commitment.
+    fn compute_blob_proof(&self, blob: &Blob, commitment: &Bytes48) -> Result<Bytes48>;
+
+    /// Returns the [CELLS_PER_EXT_BLOB] KZG proofs of the cells of the extended blob.
+    fn compute_cell_proofs(&self, blob: &Blob) -> Result<Vec<Bytes48>>;
+}
+
+/// Commits to blobs against the Ethereum mainnet KZG trusted setup, vendored as [TRUSTED_SETUP].
+///
+/// The trusted setup is loaded on first use.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EthereumKzgCommitter;
+
+impl EthereumKzgCommitter {
+    /// Returns the trusted setup, without precomputed tables.
+    fn settings(&self) -> Result<&'static c_kzg::KzgSettings> {
+        KZG_SETTINGS
+            .as_ref()
+            .map_err(|e| BlobError::Kzg(e.clone()).into())
+    }
+
+    /// Converts the [Blob] to the c-kzg blob type.
+    fn c_kzg_blob(blob: &Blob) -> Result<c_kzg::Blob> {
+        c_kzg::Blob::from_bytes(blob.as_bytes())
+            .map_err(|e| BlobError::Kzg(e.to_string()).into())
+    }
+}
+
+impl KzgCommitter for EthereumKzgCommitter {
+    fn blob_to_commitment(&self, blob: &Blob) -> Result<Bytes48> {
+        let commitment = self
+            .settings()?
+            .blob_to_kzg_commitment(&Self::c_kzg_blob(blob)?)
+            .map_err(|e| BlobError::Kzg(e.to_string()))?;
+        Ok(commitment.to_bytes().into_inner())
+    }
+
+    fn compute_blob_proof(&self, blob: &Blob, commitment: &Bytes48) -> Result<Bytes48> {
+        let proof = self
+            .settings()?
+            .compute_blob_kzg_proof(
+                &Self::c_kzg_blob(blob)?,
+                &c_kzg::Bytes48::new(*commitment),
+            )
+            .map_err(|e| BlobError::Kzg(e.to_string()))?;
+        Ok(proof.to_bytes().into_inner())
+    }
+
+    fn compute_cell_proofs(&self, blob: &Blob) -> Result<Vec<Bytes48>> {
+        let (_, proofs) = self
+            .settings()?
+            .compute_cells_and_kzg_proofs(&Self::c_kzg_blob(blob)?)
+            .map_err(|e| BlobError::Kzg(e.to_string()))?;
+        Ok(proofs
+            .iter()
+            .map(|proof| proof.to_bytes().into_inner())
+            .collect())
+    }
+}
+
+/// Returns the Osaka activation time of a known L1 chain, from which on the mempool only
+/// accepts blob sidecars with cell proofs.
+pub fn l1_osaka_time(l1_chain_id: u64) -> Option<u64> {
+    match l1_chain_id {
+        1 => Some(1764798551),
+        11155111 => Some(1760427360),
+        17000 => Some(1759308480),
+        560048 => Some(1761677592),
+        _ => None,
+    }
+}
+
+/// The version of the network wrapper of a [BlobSidecar], which determines its proofs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlobSidecarVersion {
+    /// A KZG proof of each blob against its commitment, as introduced by EIP-4844
+    #[default]
+    V0,
+    /// [CELLS_PER_EXT_BLOB] KZG proofs of the cells of each blob, since Fusaka (EIP-7594)
+    V1,
+}
+
+impl BlobSidecarVersion {
+    /// Returns the sidecar version accepted by the mempool of the L1 chain at the given time.
+    ///
+    /// Chains without a known Osaka activation time are assumed not to have activated it.
+    pub fn at(l1_chain_id: u64, timestamp: u64) -> Self {
+        match l1_osaka_time(l1_chain_id) {
+            Some(osaka_time) if timestamp >= osaka_time => Self::V1,
+            _ => Self::V0,
+        }
+    }
+}
+
+/// Returns the versioned hash of a KZG commitment, which a blob transaction commits to.
+pub fn kzg_to_versioned_hash(commitment: &Bytes48) -> H256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256::from(hash)
+}
+
+/// The blobs of a blob transaction, with their KZG commitments and proofs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlobSidecar {
+    /// The version of the network wrapper
+    pub version: BlobSidecarVersion,
+    /// The blobs
+    pub blobs: Vec<Blob>,
+    /// The KZG commitment of each blob
+    pub commitments: Vec<Bytes48>,
+    /// The KZG proof of each blob for [BlobSidecarVersion::V0], or the
+    /// [CELLS_PER_EXT_BLOB] cell proofs of each blob for [BlobSidecarVersion::V1]
+    pub proofs: Vec<Bytes48>,
+}
+
+impl BlobSidecar {
+    /// Constructs the sidecar of the given blobs, computing their commitments and the
+    /// proofs of the given version.
+    pub fn new(
+        version: BlobSidecarVersion,
+        blobs: Vec<Blob>,
+        committer: &dyn KzgCommitter,
+    ) -> Result<Self> {
+        let mut commitments = Vec::with_capacity(blobs.len());
+        let mut proofs = Vec::with_capacity(blobs.len());
+        for blob in &blobs {
+            let commitment = committer.blob_to_commitment(blob)?;
+            match version {
+                BlobSidecarVersion::V0 => {
+                    proofs.push(committer.compute_blob_proof(blob, &commitment)?)
+                }
+                BlobSidecarVersion::V1 => {
+                    proofs.extend(committer.compute_cell_proofs(blob)?)
+                }
+            }
+            commitments.push(commitment);
+        }
+        Ok(Self {
+            version,
+            blobs,
+            commitments,
+            proofs,
+        })
+    }
+
+    /// Returns the sidecar of a single zero blob.
+    ///
+    /// Its commitment and proofs are known without the trusted setup,
+    /// which makes it suitable to cancel blob transactions.
+    pub fn empty(version: BlobSidecarVersion) -> Self {
+        let num_proofs = match version {
+            BlobSidecarVersion::V0 => 1,
+            BlobSidecarVersion::V1 => CELLS_PER_EXT_BLOB,
+        };
+        Self {
+            version,
+            blobs: vec![Blob::default()],
+            commitments: vec![G1_POINT_AT_INFINITY],
+            proofs: vec![G1_POINT_AT_INFINITY; num_proofs],
+        }
+    }
+
+    /// Returns the versioned hashes of the blob commitments.
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        self.commitments.iter().map(kzg_to_versioned_hash).collect()
+    }
+}
+
+/// An EIP-4844 blob transaction, along with the sidecar of its blobs.
+///
+/// `ethers` does not support blob transactions, so they are encoded and signed here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobTransaction {
+    /// The chain id
+    pub chain_id: u64,
+    /// The address the transaction is sent from. It is not part of the encoding.
+    pub from: Address,
+    /// The nonce
+    pub nonce: U256,
+    /// The max priority fee per gas
+    pub max_priority_fee_per_gas: U256,
+    /// The max fee per gas
+    pub max_fee_per_gas: U256,
+    /// The gas limit
+    pub gas: U256,
+    /// The recipient
+    pub to: Address,
+    /// The value
+    pub value: U256,
+    /// The calldata
+    pub data: Bytes,
+    /// The max fee per blob gas
+    pub max_fee_per_blob_gas: U256,
+    /// The blobs, with their commitments and proofs
+    pub sidecar: BlobSidecar,
+}
+
+impl BlobTransaction {
+    /// Returns the hash to sign.
+    pub fn sighash(&self) -> H256 {
+        let mut stream = RlpStream::new();
+        self.rlp_fields(&mut stream, None);
+        typed_hash(&stream.out())
+    }
+
+    /// Returns the hash of the transaction signed with the given [Signature].
+    pub fn hash(&self, signature: &Signature) -> H256 {
+        let mut stream = RlpStream::new();
+        self.rlp_fields(&mut stream, Some(signature));
+        typed_hash(&stream.out())
+    }
+
+    /// Returns the network encoding of the transaction signed with the given [Signature],
+    /// as accepted by `eth_sendRawTransaction`. It depends on the [BlobSidecarVersion]:
+    /// - V0: `0x03 || rlp([tx_payload_body, blobs, commitments, proofs])`
+    /// - V1: `0x03 || rlp([tx_payload_body, 1, blobs, commitments, cell_proofs])`
+    pub fn rlp_signed(&self, signature: &Signature) -> Bytes {
+        let mut stream = match self.sidecar.version {
+            BlobSidecarVersion::V0 => RlpStream::new_list(4),
+            BlobSidecarVersion::V1 => RlpStream::new_list(5),
+        };
+        self.rlp_fields(&mut stream, Some(signature));
+        if self.sidecar.version == BlobSidecarVersion::V1 {
+            stream.append(&1u8);
+        }
+        stream.begin_list(self.sidecar.blobs.len());
+        for blob in &self.sidecar.blobs {
+            stream.append(&blob.as_bytes());
+        }
+        stream.begin_list(self.sidecar.commitments.len());
+        for commitment in &self.sidecar.commitments {
+            stream.append(&commitment.as_slice());
+        }
+        stream.begin_list(self.sidecar.proofs.len());
+        for proof in &self.sidecar.proofs {
+            stream.append(&proof.as_slice());
+        }
+        let mut encoded = vec![BLOB_TX_TYPE];
+        encoded.extend_from_slice(&stream.out());
+        encoded.into()
+    }
+
+    /// Appends the RLP list of the transaction fields, followed by the signature if given.
+    fn rlp_fields(&self, stream: &mut RlpStream, signature: Option<&Signature>) {
+        stream.begin_list(if signature.is_some() { 14 } else { 11 });
+        stream.append(&self.chain_id);
+        stream.append(&self.nonce);
+        stream.append(&self.max_priority_fee_per_gas);
+        stream.append(&self.max_fee_per_gas);
+        stream.append(&self.gas);
+        stream.append(&self.to);
+        stream.append(&self.value);
+        stream.append(&self.data.as_ref());
+        stream.begin_list(0);
+        stream.append(&self.max_fee_per_blob_gas);
+        stream.append_list(&self.sidecar.versioned_hashes());
+        if let Some(signature) = signature {
+            // Typed transactions encode the y parity rather than a legacy `v`
+            let y_parity = if signature.v >= 27 {
+                signature.v - 27
+            } else {
+                signature.v
+            };
+            stream.append(&y_parity);
+            stream.append(&signature.r);
+            stream.append(&signature.s);
+        }
+    }
+}
+
+/// Hashes an RLP payload of a blob transaction, prefixed with its transaction type.
+fn typed_hash(payload: &[u8]) -> H256 {
+    let mut encoded = vec![BLOB_TX_TYPE];
+    encoded.extend_from_slice(payload);
+    H256::from(keccak256(encoded))
+}
@@ -1,8 +1,211 @@
-use flate2::write::ZlibDecoder;
+use std::io::Write;
+
+use bytes::Bytes;
+use ethers_core::types::{
+    Block,
+    Transaction,
+};
+use eyre::Result;
+use flate2::{
+    write::ZlibEncoder,
+    Compression,
+};
+use rand::RngCore;
+
+use crate::{
+    batch::{
+        L1BlockInfo,
+        SingularBatch,
+    },
+    errors::ChannelOutError,
+    rollup::CHANNEL_TIMEOUT_GRANITE,
+    span_batch::{
+        BatchType,
+        SpanBatch,
+    },
+};
 
 /// ChannelId is a 16-byte identifier for a channel.
 pub type ChannelId = [u8; 16];
 
+/// The derivation version byte prefixed to all batcher transaction data.
+pub const DERIVATION_VERSION_0: u8 = 0x00;
+
+/// The derivation version byte prefixed to alt-DA commitments posted to the batch inbox.
+pub const DERIVATION_VERSION_1: u8 = 0x01;
+
+/// The number of bytes a [Frame] adds on top of its frame data.
+///
+/// channel_id (16) + frame_number (2) + frame_data_length (4) + is_last (1)
+pub const FRAME_OVERHEAD: usize = 23;
+
+/// The maximum size of a frame's data as accepted by the derivation pipeline.
+pub const MAX_FRAME_LEN: usize = 1_000_000;
+
+/// The maximum number of uncompressed RLP bytes a channel may contain.
+pub const MAX_RLP_BYTES_PER_CHANNEL: usize = 10_000_000;
+
+/// The default maximum size of an encoded [Frame], in bytes.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 120_000;
+
+/// The default target size of an encoded [Frame], in bytes.
+pub const DEFAULT_TARGET_FRAME_SIZE: usize = 100_000;
+
+/// The default approximate compression ratio of channel data.
+pub const DEFAULT_APPROX_COMPRESSION_RATIO: f64 = 0.4;
+
+/// The default channel timeout, in L1 blocks.
+pub const DEFAULT_CHANNEL_TIMEOUT: u64 = 300;
+
+/// Configures how L2 blocks are built into channels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelConfig {
+    /// The maximum size of an encoded [Frame], in bytes.
+    pub max_frame_size: usize,
+    /// The target size of an encoded [Frame], in bytes.
+    ///
+    /// Channels are closed once their estimated compressed size reaches
+    /// `target_frame_size * target_num_frames`.
+    pub target_frame_size: usize,
+    /// The target number of frames per channel.
+    pub target_num_frames: usize,
+    /// The approximate compression ratio, used to estimate the compressed channel size.
+    pub approx_compression_ratio: f64,
+    /// The number of L1 blocks after the first frame's inclusion within which
+    /// all frames of a channel must be included, until Granite.
+    pub channel_timeout: u64,
+    /// The L2 timestamp at which Granite fixes the channel timeout to
+    /// [CHANNEL_TIMEOUT_GRANITE], if scheduled.
+    pub granite_time: Option<u64>,
+    /// The maximum number of L1 blocks a channel is kept open before it is closed,
+    /// even if not full. A value of 0 disables the deadline.
+    pub max_channel_duration: u64,
+    /// The type of batches to write into channels.
+    pub batch_type: BatchType,
+    /// The L2 genesis timestamp, used to encode span batches.
+    pub l2_genesis_timestamp: u64,
+    /// The L2 chain id, used to encode span batches.
+    pub l2_chain_id: u64,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            target_frame_size: DEFAULT_TARGET_FRAME_SIZE,
+            target_num_frames: 1,
+            approx_compression_ratio: DEFAULT_APPROX_COMPRESSION_RATIO,
+            channel_timeout: DEFAULT_CHANNEL_TIMEOUT,
+            granite_time: None,
+            max_channel_duration: 0,
+            batch_type: BatchType::Singular,
+            l2_genesis_timestamp: 0,
+            l2_chain_id: 0,
+        }
+    }
+}
+
+impl ChannelConfig {
+    /// Returns the timeout of a channel starting with an L2 block of the given timestamp,
+    /// in L1 blocks.
+    pub fn channel_timeout_at(&self, timestamp: u64) -> u64 {
+        match self.granite_time.is_some_and(|time| timestamp >= time) {
+            true => CHANNEL_TIMEOUT_GRANITE,
+            false => self.channel_timeout,
+        }
+    }
+
+    /// Opens a new [ChannelOut], picking the batch encoder from the [BatchType].
+    pub fn open_channel(&self) -> ChannelOut {
+        match self.batch_type {
+            BatchType::Singular => ChannelOut::new(),
+            BatchType::Span => ChannelOut::new().with_span_batch(SpanBatch::new(
+                self.l2_genesis_timestamp,
+                self.l2_chain_id,
+            )),
+        }
+    }
+
+    /// Returns whether the [ChannelOut] reached its target output size.
+    ///
+    /// The compressed size is estimated from the channel's input bytes and the
+    /// approximate compression ratio.
+    pub fn is_full(&self, channel: &ChannelOut) -> bool {
+        let target = self.target_frame_size * self.target_num_frames;
+        channel.input_bytes() as f64 * self.approx_compression_ratio >= target as f64
+    }
+}
+
+/// A Frame is a chunk of compressed channel data, as posted to L1.
+///
+/// Frames are encoded as:
+/// `channel_id ++ frame_number ++ frame_data_length ++ frame_data ++ is_last`
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Frame {
+    /// The channel this frame belongs to.
+    pub id: ChannelId,
+    /// The position of this frame within the channel.
+    pub number: u16,
+    /// The frame data.
+    pub data: Vec<u8>,
+    /// Whether this is the last frame of the channel.
+    pub is_last: bool,
+}
+
+impl Frame {
+    /// Encodes the [Frame] into its wire format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(FRAME_OVERHEAD + self.data.len());
+        out.extend_from_slice(&self.id);
+        out.extend_from_slice(&self.number.to_be_bytes());
+        out.extend_from_slice(&(self.data.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.data);
+        out.push(self.is_last as u8);
+        out
+    }
+
+    /// Decodes a single [Frame] from the front of the given bytes.
+    ///
+    /// Returns the decoded [Frame] along with the number of bytes consumed.
+    pub fn decode(data: &[u8]) -> Result<(Self, usize)> {
+        if data.len() < FRAME_OVERHEAD {
+            return Err(ChannelOutError::InvalidFrame.into())
+        }
+        let mut id = ChannelId::default();
+        id.copy_from_slice(&data[..16]);
+        let number = u16::from_be_bytes([data[16], data[17]]);
+        let length =
+            u32::from_be_bytes([data[18], data[19], data[20], data[21]]) as usize;
+        if length > MAX_FRAME_LEN || data.len() < FRAME_OVERHEAD + length {
+            return Err(ChannelOutError::InvalidFrame.into())
+        }
+        let frame_data = data[22..22 + length].to_vec();
+        let is_last = match data[22 + length] {
+            0 => false,
+            1 => true,
+            _ => return Err(ChannelOutError::InvalidFrame.into()),
+        };
+        let frame = Self {
+            id,
+            number,
+            data: frame_data,
+            is_last,
+        };
+        Ok((frame, FRAME_OVERHEAD + length))
+    }
+
+    /// Builds the batcher transaction data for the given frames.
+    ///
+    /// The resulting bytes are the [DERIVATION_VERSION_0] byte followed by the encoded frames.
+    pub fn tx_data(frames: &[Frame]) -> Bytes {
+        let mut out = vec![DERIVATION_VERSION_0];
+        for frame in frames {
+            out.extend(frame.encode());
+        }
+        Bytes::from(out)
+    }
+}
+
 /// The Output Channel
 #[derive(Debug)]
 pub struct ChannelOut {
@@ -16,9 +219,181 @@ pub struct ChannelOut {
     pub rlp_length: usize,
     /// The compressor stage.
     /// Write input data to it.
-    pub compress: ZlibDecoder<Vec<u8>>,
+    pub compress: ZlibEncoder<Vec<u8>>,
     /// The post-compression buffer.
     pub buf: Vec<u8>,
     /// Whether the channel is closed.
     pub closed: bool,
+    /// The pending span batch, if the channel is built from a [SpanBatch].
+    ///
+    /// A span batch is only written to the compressor when the channel is closed.
+    pub span_batch: Option<SpanBatch>,
+}
+
+impl Default for ChannelOut {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChannelOut {
+    /// Constructs a new [ChannelOut] with a random [ChannelId].
+    pub fn new() -> Self {
+        let mut id = ChannelId::default();
+        rand::thread_rng().fill_bytes(&mut id);
+        Self {
+            id,
+            frame: 0,
+            rlp_length: 0,
+            compress: ZlibEncoder::new(Vec::new(), Compression::best()),
+            buf: Vec::new(),
+            closed: false,
+            span_batch: None,
+        }
+    }
+
+    /// Builds the channel from a single [SpanBatch] instead of [SingularBatch]es.
+    pub fn with_span_batch(mut self, span_batch: SpanBatch) -> Self {
+        self.span_batch = Some(span_batch);
+        self
+    }
+
+    /// Adds an L2 block to the channel.
+    ///
+    /// The block is converted into a [SingularBatch] which is either written to
+    /// the channel directly, or appended to the channel's [SpanBatch]. A span batch
+    /// is only encoded when the channel is closed.
+    pub fn add_block(&mut self, block: &Block<Transaction>) -> Result<()> {
+        if self.closed {
+            return Err(ChannelOutError::ChannelClosed.into())
+        }
+        let batch = SingularBatch::from_block(block)?;
+        let span_batch = match &mut self.span_batch {
+            Some(span_batch) => span_batch,
+            None => return self.add_batch(&batch.rlp_bytes()),
+        };
+
+        let sequence_number = L1BlockInfo::from_block(block)?.sequence_number;
+        span_batch.append(batch, sequence_number)?;
+        let rlp_length = span_batch.rlp_len();
+        if rlp_length > MAX_RLP_BYTES_PER_CHANNEL {
+            span_batch.pop();
+            return Err(ChannelOutError::TooManyRlpBytes.into())
+        }
+        self.rlp_length = rlp_length;
+        Ok(())
+    }
+
+    /// Returns the number of uncompressed RLP bytes added to the channel.
+    pub fn input_bytes(&self) -> usize {
+        self.rlp_length
+    }
+
+    /// Returns whether the last frame of the channel was emitted.
+    ///
+    /// Closing the channel always buffers the end of the compressed stream,
+    /// so an empty buffer on a closed channel means the last frame is out.
+    pub fn is_complete(&self) -> bool {
+        self.closed && self.buf.is_empty()
+    }
+
+    /// Returns the number of compressed bytes buffered and ready to be emitted as frame data.
+    ///
+    /// The compressor holds back some of its output until it is flushed or closed.
+    pub fn ready_bytes(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Adds an RLP-encoded batch to the channel, streaming it through the compressor.
+    ///
+    /// Returns [ChannelOutError::TooManyRlpBytes] if the batch would push the channel
+    /// past [MAX_RLP_BYTES_PER_CHANNEL]. In that case the batch is not added and
+    /// the channel should be closed.
+    pub fn add_batch(&mut self, rlp: &[u8]) -> Result<()> {
+        if self.closed {
+            return Err(ChannelOutError::ChannelClosed.into())
+        }
+        if self.rlp_length + rlp.len() > MAX_RLP_BYTES_PER_CHANNEL {
+            return Err(ChannelOutError::TooManyRlpBytes.into())
+        }
+        self.rlp_length += rlp.len();
+        self.compress.write_all(rlp)?;
+        self.drain_compressor();
+        Ok(())
+    }
+
+    /// Flushes the compressor, making all input data so far available as frame data.
+    ///
+    /// Flushing hurts the compression ratio, so it should be used sparingly.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.closed {
+            return Err(ChannelOutError::ChannelClosed.into())
+        }
+        self.compress.flush()?;
+        self.drain_compressor();
+        Ok(())
+    }
+
+    /// Closes the channel, finishing the compressed stream.
+    ///
+    /// No more batches may be added once the channel is closed.
+    pub fn close(&mut self) -> Result<()> {
+        if self.closed {
+            return Err(ChannelOutError::ChannelClosed.into())
+        }
+        if let Some(span_batch) = self.span_batch.as_ref().filter(|s| !s.is_empty()) {
+            self.compress.write_all(&span_batch.rlp_bytes()?)?;
+        }
+        self.compress.try_finish()?;
+        self.drain_compressor();
+        self.closed = true;
+        Ok(())
+    }
+
+    /// Moves compressed output from the compressor stage into the post-compression buffer.
+    fn drain_compressor(&mut self) {
+        self.buf.append(self.compress.get_mut());
+    }
+
+    /// Outputs the next [Frame] from the channel's buffered data.
+    ///
+    /// The encoded frame is at most `max_size` bytes, including the [FRAME_OVERHEAD].
+    /// The last frame is only emitted once the channel is closed and all
+    /// buffered data has been consumed. Returns `None` while no data is buffered,
+    /// since closing the channel always buffers the end of the compressed stream.
+    pub fn output_frame(&mut self, max_size: usize) -> Result<Option<Frame>> {
+        if max_size <= FRAME_OVERHEAD {
+            return Err(ChannelOutError::MaxFrameSizeTooSmall.into())
+        }
+        if self.buf.is_empty() {
+            return Ok(None)
+        }
+        let number =
+            u16::try_from(self.frame).map_err(|_| ChannelOutError::TooManyFrames)?;
+
+        // Clamp the data to the space left in the frame after the overhead.
+        let max_data_size = (max_size - FRAME_OVERHEAD).min(MAX_FRAME_LEN);
+        let data_len = self.buf.len().min(max_data_size);
+        let is_last = self.closed && data_len == self.buf.len();
+        let data: Vec<u8> = self.buf.drain(..data_len).collect();
+
+        self.frame += 1;
+        Ok(Some(Frame {
+            id: self.id,
+            number,
+            data,
+            is_last,
+        }))
+    }
+
+    /// Outputs the next [Frame] as batcher transaction data.
+    ///
+    /// See [ChannelOut::output_frame] and [Frame::tx_data].
+    pub fn output_tx_data(&mut self, max_size: usize) -> Result<Option<(Frame, Bytes)>> {
+        let Some(frame) = self.output_frame(max_size)? else {
+            return Ok(None)
+        };
+        let tx_data = Frame::tx_data(std::slice::from_ref(&frame));
+        Ok(Some((frame, tx_data)))
+    }
 }
@@ -1,6 +1,11 @@
 use crate::client::Archon;
 use bytes::Bytes;
-use ethers_core::types::BlockId;
+use ethers_core::types::{
+    Block,
+    BlockId,
+    BlockNumber,
+    Transaction,
+};
 use ethers_providers::{
     Http,
     Middleware,
@@ -8,13 +13,17 @@ use ethers_providers::{
 };
 use eyre::Result;
 use std::{
-    collections::BTreeMap,
+    collections::{
+        BTreeMap,
+        VecDeque,
+    },
     fmt::{
         self,
         Display,
     },
     pin::Pin,
     sync::{
+        atomic::Ordering,
         mpsc::{
             channel,
             Receiver,
@@ -28,32 +37,200 @@ use std::{
 use tokio::task::JoinHandle;
 
 use crate::{
-    errors::ChannelManagerError,
+    builder::{
+        ChannelConfig,
+        ChannelId,
+        ChannelOut,
+        FRAME_OVERHEAD,
+    },
+    driver::DriverEvent,
+    errors::{
+        ChannelManagerError,
+        ChannelOutError,
+    },
+    metrics::L2_REORG_RESETS,
+    nonce::NonceManager,
     pipeline_builder::Stage,
-    rollup::RollupNode,
-    state::State,
+    rollup::{
+        BlockNumHash,
+        RollupNode,
+        SyncStatus,
+    },
+    state::{
+        BlockUpdate,
+        State,
+    },
+    transactions::{
+        Cancellation,
+        TransactionOutcome,
+    },
 };
 
+/// The maximum number of unsafe L2 blocks fetched before they are stored in the [State].
+pub const MAX_UNSAFE_BLOCKS_PER_CHUNK: u64 = 64;
+
 /// Channel Manager
 #[derive(Debug, Default)]
 pub struct ChannelManager {
     /// Internal [State] Manager
     state: Arc<Mutex<State>>,
-    /// A channel to send [Bytes] back to the [crate::client::Archon] orchestrator
-    sender: Option<Sender<Pin<Box<Bytes>>>>,
-    /// A channel to receive [BlockId] messages from the [crate::client::Archon] orchestrator
-    receiver: Option<Receiver<Pin<Box<BlockId>>>>,
+    /// A channel to send [TaggedData] back to the [crate::client::Archon] orchestrator
+    sender: Option<Sender<Pin<Box<TaggedData>>>>,
+    /// A channel to receive [DriverEvent] messages from the [crate::client::Archon] orchestrator
+    receiver: Option<Receiver<Pin<Box<DriverEvent>>>>,
     /// An internal map of pending transactions.
     pending_txs: BTreeMap<TransactionID, Bytes>,
     /// An internal map of confirmed transactions.
     confirmed_txs: BTreeMap<TransactionID, BlockId>,
     /// A block receiver
-    block_recv: Option<Receiver<Pin<Box<BlockId>>>>,
+    block_recv: Option<Receiver<Pin<Box<DriverEvent>>>>,
+    /// A channel to receive [TransactionOutcome]s of submitted frames
+    outcome_recv: Option<Receiver<Pin<Box<TransactionOutcome>>>>,
+    /// Configures how L2 blocks are built into channels
+    channel_config: ChannelConfig,
+    /// L2 blocks loaded from [State] that are not yet added to a channel
+    blocks: VecDeque<Block<Transaction>>,
+    /// Channels with frames still to be submitted, oldest first.
+    /// Only the newest channel may still be open.
+    channels: VecDeque<PendingChannel>,
+    /// The latest L1 block number received
+    l1_head: Option<u64>,
+    /// The [NonceManager] of the [crate::transactions::TransactionManager], resynced on L1 reorgs
+    nonce_manager: Option<NonceManager>,
+    /// Cancels the transactions in flight when pending channels are discarded
+    cancellation: Option<Cancellation>,
 }
 
 /// PendingChannel is a constructed pending channel
-#[derive(Debug, Clone, Hash, PartialEq, PartialOrd)]
-pub struct PendingChannel {}
+#[derive(Debug)]
+pub struct PendingChannel {
+    /// The channel being built
+    channel: ChannelOut,
+    /// The L2 blocks added to the channel
+    blocks: Vec<Block<Transaction>>,
+    /// Frames output by the channel, waiting to be submitted
+    frames: VecDeque<TaggedData>,
+    /// The number of frames output by the channel so far
+    num_frames: u64,
+    /// The lowest L1 block number a frame of the channel was confirmed in
+    min_inclusion_block: Option<u64>,
+    /// The highest L1 block number a frame of the channel was confirmed in
+    max_inclusion_block: Option<u64>,
+    /// The L1 block number at which the channel is closed, even if not full
+    duration_deadline: Option<u64>,
+}
+
+impl PendingChannel {
+    /// Constructs a new [PendingChannel] around an open [ChannelOut].
+    pub fn new(channel: ChannelOut) -> Self {
+        Self {
+            channel,
+            blocks: Vec::new(),
+            frames: VecDeque::new(),
+            num_frames: 0,
+            min_inclusion_block: None,
+            max_inclusion_block: None,
+            duration_deadline: None,
+        }
+    }
+
+    /// Returns the [ChannelId] of the pending channel.
+    pub fn id(&self) -> ChannelId {
+        self.channel.id
+    }
+
+    /// Returns the L2 blocks added to the pending channel.
+    pub fn blocks(&self) -> &[Block<Transaction>] {
+        &self.blocks
+    }
+
+    /// Returns whether the underlying [ChannelOut] is closed.
+    pub fn is_closed(&self) -> bool {
+        self.channel.closed
+    }
+
+    /// Returns whether all frames of the channel were output and confirmed on L1.
+    pub fn is_fully_confirmed(
+        &self,
+        confirmed_txs: &BTreeMap<TransactionID, BlockId>,
+    ) -> bool {
+        self.channel.is_complete()
+            && self.frames.is_empty()
+            && self.tx_ids().all(|id| confirmed_txs.contains_key(&id))
+    }
+
+    /// Sets the L1 block number at which the channel is closed, even if not full.
+    pub fn with_duration_deadline(mut self, deadline: u64) -> Self {
+        self.duration_deadline = Some(deadline);
+        self
+    }
+
+    /// Returns the L1 block number at which the channel is closed, even if not full.
+    pub fn duration_deadline(&self) -> Option<u64> {
+        self.duration_deadline
+    }
+
+    /// Returns the L1 block number the first frame of the channel was confirmed in.
+    pub fn first_inclusion_block(&self) -> Option<u64> {
+        self.min_inclusion_block
+    }
+
+    /// Returns whether the channel's frames span more than `channel_timeout` L1 blocks.
+    ///
+    /// While the channel is not fully confirmed, its remaining frames can at best
+    /// be included after the current `l1_head`.
+    pub fn is_timed_out(
+        &self,
+        l1_head: Option<u64>,
+        channel_timeout: u64,
+        confirmed_txs: &BTreeMap<TransactionID, BlockId>,
+    ) -> bool {
+        let Some(first) = self.min_inclusion_block else {
+            return false
+        };
+        let last = match self.is_fully_confirmed(confirmed_txs) {
+            true => self.max_inclusion_block,
+            false => self.max_inclusion_block.max(l1_head),
+        };
+        last.unwrap_or(first).saturating_sub(first) >= channel_timeout
+    }
+
+    /// Records that a frame of the channel was confirmed in the given L1 block.
+    fn record_inclusion(&mut self, l1_block_number: u64) {
+        self.min_inclusion_block = Some(
+            self.min_inclusion_block
+                .map_or(l1_block_number, |min| min.min(l1_block_number)),
+        );
+        self.max_inclusion_block = Some(
+            self.max_inclusion_block
+                .map_or(l1_block_number, |max| max.max(l1_block_number)),
+        );
+    }
+
+    /// Returns the [TransactionID]s of all frames output by the channel so far.
+    fn tx_ids(&self) -> impl Iterator<Item = TransactionID> + '_ {
+        (0..self.num_frames).map(|number| TransactionID::new(self.id(), number))
+    }
+
+    /// Outputs all frames that are ready into the frame queue.
+    ///
+    /// While the channel is open, only full frames are output.
+    /// Once closed, the remaining data is output, ending with the last frame.
+    fn output_frames(&mut self, max_frame_size: usize) -> Result<()> {
+        let full_frame = max_frame_size.saturating_sub(FRAME_OVERHEAD);
+        while (!self.channel.closed && self.channel.ready_bytes() >= full_frame)
+            || (self.channel.closed && !self.channel.is_complete())
+        {
+            let Some((frame, data)) = self.channel.output_tx_data(max_frame_size)? else {
+                break
+            };
+            let id = TransactionID::new(frame.id, frame.number as u64);
+            self.frames.push_back(TaggedData::new(data, id));
+            self.num_frames += 1;
+        }
+        Ok(())
+    }
+}
 
 // impl Iterator for ChannelManager {
 //     type Item = PendingChannel;
@@ -72,8 +249,8 @@ impl ChannelManager {
 
     /// Sets the [ChannelManager] sender.
     ///
-    /// This [std::sync::mpsc::channel] is used to send [Bytes] back to the [crate::client::Archon] orchestrator.
-    pub fn with_sender(&mut self, sender: Sender<Pin<Box<Bytes>>>) -> &mut Self {
+    /// This [std::sync::mpsc::channel] is used to send [TaggedData] back to the [crate::client::Archon] orchestrator.
+    pub fn with_sender(&mut self, sender: Sender<Pin<Box<TaggedData>>>) -> &mut Self {
         self.sender = Some(sender);
         self
     }
@@ -81,19 +258,66 @@ impl ChannelManager {
     /// Sets the [ChannelManager] receiver.
     ///
     /// This [std::sync::mpsc::channel] is used by the [crate::client::Archon] orchestrator to send
-    /// [BlockId] messages to the [ChannelManager]. [BlockId]s sent through this channel are expected
-    /// to be the latest L1 [BlockId] fetched via a [ethers_providers::Provider].
-    ///
-    /// Optionally, the [ChannelManager] should validate that the [BlockId] is the valid latest L1 [BlockId].
-    pub fn with_receiver(&mut self, receiver: Receiver<Pin<Box<BlockId>>>) -> &mut Self {
+    /// [DriverEvent] messages to the [ChannelManager]. [DriverEvent]s sent through this channel carry
+    /// the latest L1 head fetched via a [ethers_providers::Provider], and whether L1 reorged.
+    pub fn with_receiver(
+        &mut self,
+        receiver: Receiver<Pin<Box<DriverEvent>>>,
+    ) -> &mut Self {
         self.receiver = Some(receiver);
         self
     }
 
+    /// Sets the [ChannelConfig] used to build L2 blocks into channels.
+    ///
+    /// The [ChannelConfig]'s [crate::span_batch::BatchType] picks the batch encoder.
+    pub fn with_channel_config(&mut self, channel_config: ChannelConfig) -> &mut Self {
+        self.channel_config = channel_config;
+        self
+    }
+
+    /// Sets the [ChannelManager] outcome receiver.
+    ///
+    /// This [std::sync::mpsc::channel] is used by the [crate::client::Archon] orchestrator to report
+    /// the [TransactionOutcome] of every frame the [ChannelManager] sent out.
+    pub fn with_outcome_receiver(
+        &mut self,
+        outcome_recv: Receiver<Pin<Box<TransactionOutcome>>>,
+    ) -> &mut Self {
+        self.outcome_recv = Some(outcome_recv);
+        self
+    }
+
+    /// Sets the [NonceManager] shared with the [crate::transactions::TransactionManager].
+    ///
+    /// It is reset on L1 reorgs, since reorged transactions may have returned to the mempool.
+    pub fn with_nonce_manager(&mut self, nonce_manager: NonceManager) -> &mut Self {
+        self.nonce_manager = Some(nonce_manager);
+        self
+    }
+
+    /// Sets the [Cancellation] handle shared with the [crate::transactions::TransactionManager].
+    ///
+    /// Cancellation is requested whenever an L2 reorg reset discards the pending channels.
+    pub fn with_cancellation(&mut self, cancellation: Cancellation) -> &mut Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Returns the channels with frames that are not yet confirmed, oldest first.
+    pub fn channels(&self) -> &VecDeque<PendingChannel> {
+        &self.channels
+    }
+
+    /// Returns a handle to the [ChannelManager]'s shared [State].
+    pub fn state(&self) -> Arc<Mutex<State>> {
+        self.state.clone()
+    }
+
     /// Sets the [ChannelManager] receiever
     pub fn receive_blocks(
         &mut self,
-        block_recv: Option<Receiver<Pin<Box<BlockId>>>>,
+        block_recv: Option<Receiver<Pin<Box<DriverEvent>>>>,
     ) -> &mut Self {
         self.block_recv = block_recv;
         self
@@ -105,55 +329,318 @@ impl ChannelManager {
     /// It currently only uses one frame per transaction. If the pending channel is
     /// full, it only returns the remaining frames of this channel until it got
     /// successfully fully sent to L1. It returns an error if there's no pending frame.
-    pub fn tx_data(block_id: BlockId) -> Result<(Bytes, TransactionID)> {
+    pub fn tx_data(&mut self, block_id: BlockId) -> Result<(Bytes, TransactionID)> {
         tracing::debug!(target: "archon::channels", "channel manager constructing tx data with block id: {:?}...", block_id);
-        // TODO: implement
-        Err(ChannelManagerError::NotImplemented.into())
+        let (reset, blocks) = {
+            let mut state = self
+                .state
+                .lock()
+                .map_err(|_| eyre::eyre!("Failed to lock state to load blocks"))?;
+            (state.take_reset(), state.take_blocks())
+        };
+        if reset {
+            tracing::warn!(target: "archon::channels", "L2 reorg reset the state, discarding {} pending channels", self.channels.len());
+            self.clear_pending_channels()?;
+            if let Some(cancellation) = &self.cancellation {
+                cancellation.cancel();
+            }
+        }
+        self.blocks.extend(blocks);
+        if let Some(number) = block_number(&block_id) {
+            self.l1_head = Some(number);
+        }
+        self.check_timeouts(block_number(&block_id));
+        self.process_blocks_into_channels()?;
+        self.check_duration_deadline()?;
+        for pending in self.channels.iter_mut() {
+            pending.output_frames(self.channel_config.max_frame_size)?;
+        }
+
+        let tagged = self
+            .channels
+            .iter_mut()
+            .find_map(|pending| pending.frames.pop_front())
+            .ok_or(ChannelManagerError::NoTxData)?;
+        self.pending_txs
+            .insert(tagged.id.clone(), tagged.data.clone());
+        Ok((tagged.data, tagged.id))
     }
 
-    /// Executes the [ChannelManager].
-    pub async fn execute(
-        block_recv: Option<Receiver<Pin<Box<BlockId>>>>,
-        receiver: Arc<Mutex<Receiver<Pin<Box<BlockId>>>>>,
-        sender: Arc<Mutex<Sender<Pin<Box<Bytes>>>>>,
-    ) -> Result<()> {
-        let mut pending_txs = BTreeMap::new();
-        loop {
-            // Read block id from the receiver.
-            // This will block until a new block id is received.
-            let block_id = if let Some(block_recv) = &block_recv {
-                block_recv
-                    .recv()
-                    .map_err(|_| ChannelManagerError::ChannelClosed)?
-            } else {
-                let locked_receiver = receiver
-                    .lock()
-                    .map_err(|_| ChannelManagerError::ReceiverLock)?;
-                locked_receiver
-                    .recv()
-                    .map_err(|_| ChannelManagerError::ChannelClosed)?
+    /// Marks the transaction of a frame as confirmed in the given L1 block.
+    ///
+    /// Once all frames of a channel are confirmed, the channel is dropped.
+    pub fn tx_confirmed(&mut self, id: TransactionID, l1_block: BlockId) {
+        if self.pending_txs.remove(&id).is_none() {
+            tracing::warn!(target: "archon::channels", "Confirmed unknown transaction {}", id);
+            return
+        }
+        tracing::debug!(target: "archon::channels", "Transaction {} confirmed in L1 block {:?}", id, l1_block);
+        if let Some(number) = block_number(&l1_block) {
+            if let Some(pending) = self
+                .channels
+                .iter_mut()
+                .find(|pending| hex::encode(pending.id()) == id.channel_id)
+            {
+                pending.record_inclusion(number);
+            }
+        }
+        self.confirmed_txs.insert(id, l1_block);
+        self.check_timeouts(None);
+
+        while let Some(pending) = self.channels.front() {
+            if !pending.is_fully_confirmed(&self.confirmed_txs) {
+                break
+            }
+            for id in pending.tx_ids() {
+                self.confirmed_txs.remove(&id);
+            }
+            tracing::info!(target: "archon::channels", "Channel {} fully confirmed", hex::encode(pending.id()));
+            self.channels.pop_front();
+        }
+    }
+
+    /// Marks the transaction of a frame as failed.
+    ///
+    /// The frame is requeued so that it is returned by the next call to [ChannelManager::tx_data].
+    /// Frames take their nonces in the order they are handed out, so a resubmitted frame would
+    /// land on L1 after any frame handed out since, and derivation drops channels with frames
+    /// out of order. If a later frame of the channel or a frame of a newer channel was handed
+    /// out, the channel is invalidated along with all newer channels instead, and their blocks
+    /// are re-batched into frames that take new nonces in order.
+    pub fn tx_failed(&mut self, id: TransactionID) {
+        let Some(data) = self.pending_txs.remove(&id) else {
+            tracing::warn!(target: "archon::channels", "Failed unknown transaction {}", id);
+            return
+        };
+        let Some(index) = self
+            .channels
+            .iter()
+            .position(|pending| hex::encode(pending.id()) == id.channel_id)
+        else {
+            tracing::warn!(target: "archon::channels", "Transaction {} failed for a dropped channel", id);
+            return
+        };
+        let later_frames_out = self
+            .channels
+            .iter()
+            .skip(index)
+            .flat_map(|pending| pending.tx_ids())
+            .filter(|other| {
+                other.channel_id != id.channel_id || other.frame_number > id.frame_number
+            })
+            .any(|other| {
+                self.pending_txs.contains_key(&other)
+                    || self.confirmed_txs.contains_key(&other)
+            });
+        if later_frames_out {
+            tracing::warn!(target: "archon::channels", "Transaction {} failed after later frames were submitted", id);
+            self.invalidate_channels(index, "has a frame out of order");
+            return
+        }
+        tracing::warn!(target: "archon::channels", "Transaction {} failed, requeueing frame", id);
+        self.channels[index]
+            .frames
+            .push_front(TaggedData::new(data, id));
+    }
+
+    /// Closes the open channel once the L1 head reached its duration deadline,
+    /// even if the channel is not full.
+    fn check_duration_deadline(&mut self) -> Result<()> {
+        let Some(l1_head) = self.l1_head else {
+            return Ok(())
+        };
+        let Some(pending) = self.channels.back_mut() else {
+            return Ok(())
+        };
+        if pending.is_closed() || pending.blocks.is_empty() {
+            return Ok(())
+        }
+        if pending
+            .duration_deadline
+            .is_some_and(|deadline| l1_head >= deadline)
+        {
+            pending.channel.close()?;
+            tracing::info!(target: "archon::channels", "Closed channel {} with {} blocks at its duration deadline", hex::encode(pending.id()), pending.blocks.len());
+        }
+        Ok(())
+    }
+
+    /// Invalidates the oldest timed out channel along with all newer channels.
+    ///
+    /// The timeout of each channel applies at the timestamp of its first L2 block.
+    /// The L2 blocks of the invalidated channels are requeued in order,
+    /// so that they are re-batched into fresh channels.
+    fn check_timeouts(&mut self, l1_head: Option<u64>) {
+        let Some(index) = self.channels.iter().position(|pending| {
+            let timeout = match pending.blocks.first() {
+                Some(block) => self
+                    .channel_config
+                    .channel_timeout_at(block.timestamp.as_u64()),
+                None => self.channel_config.channel_timeout,
             };
-            let (tx_data, tx_id) = Self::tx_data(*block_id)?;
-            let locked_sender =
-                sender.lock().map_err(|_| ChannelManagerError::SenderLock)?;
-            locked_sender.send(Box::pin(tx_data.clone()))?;
-            pending_txs.insert(tx_id, tx_data);
+            pending.is_timed_out(l1_head, timeout, &self.confirmed_txs)
+        }) else {
+            return
+        };
+        self.invalidate_channels(index, "timed out");
+    }
+
+    /// Invalidates all channels with a frame confirmed above the L1 reorg's common ancestor,
+    /// along with all newer channels.
+    ///
+    /// The L2 blocks of the invalidated channels are requeued in order,
+    /// so that they are re-batched into fresh channels.
+    pub fn l1_reorg(&mut self, common_ancestor: u64) {
+        tracing::warn!(target: "archon::channels", "{}, invalidating confirmations above L1 block {}", ChannelManagerError::L1Reorg, common_ancestor);
+        let reorged = |pending: &PendingChannel| {
+            pending
+                .max_inclusion_block
+                .is_some_and(|number| number > common_ancestor)
+        };
+        if let Some(index) = self.channels.iter().position(reorged) {
+            self.invalidate_channels(index, "reorged out of L1");
+        }
+        if self.l1_head.is_some_and(|head| head > common_ancestor) {
+            self.l1_head = Some(common_ancestor);
+        }
+        if let Some(nonce_manager) = &self.nonce_manager {
+            if let Err(e) = nonce_manager.reset() {
+                tracing::error!(target: "archon::channels", "Failed to reset nonce after L1 reorg: {:?}", e);
+            }
         }
     }
 
-    /// Spawns the [ChannelManager] into a new thread
-    pub fn spawn(self) -> Result<tokio::task::JoinHandle<Result<()>>> {
-        let receiver = self
-            .receiver
-            .ok_or(eyre::eyre!("ChannelManager missing receiver!"))?;
-        let receiver = Arc::new(Mutex::new(receiver));
+    /// Drops the channel at `index` and all newer channels, requeueing their L2 blocks in order.
+    fn invalidate_channels(&mut self, index: usize, reason: &str) {
+        let mut blocks = VecDeque::new();
+        for pending in self.channels.drain(index..) {
+            tracing::warn!(target: "archon::channels", "Channel {} {}, re-batching {} blocks", hex::encode(pending.id()), reason, pending.blocks.len());
+            for id in pending.tx_ids() {
+                self.pending_txs.remove(&id);
+                self.confirmed_txs.remove(&id);
+            }
+            blocks.extend(pending.blocks);
+        }
+        blocks.append(&mut self.blocks);
+        self.blocks = blocks;
+    }
+
+    /// Applies all [TransactionOutcome]s received since the last call.
+    fn process_outcomes(&mut self) {
+        let outcomes = match &self.outcome_recv {
+            Some(outcome_recv) => outcome_recv.try_iter().collect::<Vec<_>>(),
+            None => return,
+        };
+        for outcome in outcomes {
+            match *Pin::into_inner(outcome) {
+                TransactionOutcome::Confirmed(id, receipt) => {
+                    let l1_block = receipt.block_number.unwrap_or_default();
+                    self.tx_confirmed(id, BlockId::Number(l1_block.into()));
+                }
+                TransactionOutcome::Failed(id) => self.tx_failed(id),
+            }
+        }
+    }
+
+    /// Adds queued L2 blocks to the current channel, opening new channels as needed.
+    ///
+    /// A channel is closed once it is full, either by reaching the target output
+    /// size or by exceeding the maximum RLP bytes per channel.
+    fn process_blocks_into_channels(&mut self) -> Result<()> {
+        while let Some(block) = self.blocks.pop_front() {
+            if self.channels.back().is_none_or(|c| c.is_closed()) {
+                let pending = self.construct_pending_channel()?;
+                tracing::info!(target: "archon::channels", "Opened channel {}", hex::encode(pending.id()));
+                self.channels.push_back(pending);
+            }
+            let pending = self
+                .channels
+                .back_mut()
+                .ok_or(ChannelManagerError::NoTxData)?;
+            let full = match pending.channel.add_block(&block) {
+                Ok(()) => {
+                    pending.blocks.push(block);
+                    self.channel_config.is_full(&pending.channel)
+                }
+                Err(e) => {
+                    self.blocks.push_front(block);
+                    match e.downcast_ref::<ChannelOutError>() {
+                        // The block goes into the next channel.
+                        Some(ChannelOutError::TooManyRlpBytes)
+                            if !pending.blocks.is_empty() =>
+                        {
+                            true
+                        }
+                        _ => return Err(e),
+                    }
+                }
+            };
+            if full {
+                pending.channel.close()?;
+                tracing::info!(target: "archon::channels", "Closed full channel {} with {} blocks", hex::encode(pending.id()), pending.blocks.len());
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes the [ChannelManager].
+    ///
+    /// On every [DriverEvent] received, L1 reorgs and the outcomes of submitted frames
+    /// are applied and all available transaction data is sent on.
+    pub async fn execute(mut self) -> Result<()> {
         let sender = self
             .sender
+            .take()
             .ok_or(eyre::eyre!("ChannelManager missing sender!"))?;
-        let sender = Arc::new(Mutex::new(sender));
+        let receiver = self
+            .block_recv
+            .take()
+            .or(self.receiver.take())
+            .ok_or(eyre::eyre!("ChannelManager missing receiver!"))?;
+        loop {
+            // Read the driver event from the receiver.
+            // This will block until a new event is received.
+            let event = receiver
+                .recv()
+                .map_err(|_| ChannelManagerError::ChannelClosed)?;
+            if let DriverEvent::Reorg {
+                common_ancestor, ..
+            } = *event
+            {
+                self.l1_reorg(common_ancestor);
+            }
+            self.process_outcomes();
+            let block_id = event.head().id();
+            loop {
+                let (tx_data, tx_id) = match self.tx_data(block_id) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        if !matches!(
+                            e.downcast_ref::<ChannelManagerError>(),
+                            Some(ChannelManagerError::NoTxData)
+                        ) {
+                            tracing::error!(target: "archon::channels", "Failed to construct tx data: {:?}", e);
+                        }
+                        break
+                    }
+                };
+                tracing::debug!(target: "archon::channels", "Sending tx data for {}", tx_id);
+                sender.send(Box::pin(TaggedData::new(tx_data, tx_id)))?;
+            }
+        }
+    }
+
+    /// Spawns the [ChannelManager] into a new thread
+    pub fn spawn(self) -> Result<tokio::task::JoinHandle<Result<()>>> {
+        if self.sender.is_none() {
+            return Err(eyre::eyre!("ChannelManager missing sender!"))
+        }
+        if self.block_recv.is_none() && self.receiver.is_none() {
+            return Err(eyre::eyre!("ChannelManager missing receiver!"))
+        }
         let channel_manager_handle = tokio::spawn(async move {
             tracing::info!(target: "archon::channels", "Spawned ChannelManager in a new thread");
-            ChannelManager::execute(self.block_recv, receiver, sender).await
+            self.execute().await
         });
         Ok(channel_manager_handle)
     }
@@ -178,6 +665,9 @@ impl ChannelManager {
     }
 
     /// Handles the processing of L2 blocks.
+    ///
+    /// When an L2 reorg is detected, the [State] is reset, which makes the [ChannelManager]
+    /// discard its pending channels, and loading resumes from the safe head.
     pub async fn process_blocks(
         rollup_node: RollupNode,
         l2_node: Provider<Http>,
@@ -186,7 +676,7 @@ impl ChannelManager {
     ) -> Result<()> {
         tracing::info!(target: "archon::channels", "Executing block processor...");
         let mut first_iter = true;
-        let mut last_stored_block_number = 0;
+        let mut last_stored_block = None;
         loop {
             // Await the poll interval at the loop start so we can ergonomically continue below.
             if !first_iter {
@@ -194,53 +684,194 @@ impl ChannelManager {
             }
             first_iter = false;
 
-            // Calculate the range of L2 blocks to process
-            let (start_block, end_block) = {
-                let sync_status = match rollup_node.sync_status().await {
-                    Ok(sync_status) => sync_status,
-                    Err(err) => {
-                        tracing::error!(target: "archon::channels", "Failed to fetch rollup node sync status: {:?}", err);
-                        continue
-                    }
-                };
-                if sync_status.head_l1 == 0 {
-                    tracing::warn!(target: "archon::channels", "Rollup node is not synced yet. Waiting for rollup node to sync...");
+            let sync_status = match rollup_node.sync_status().await {
+                Ok(sync_status) => sync_status,
+                Err(err) => {
+                    tracing::error!(target: "archon::channels", "Failed to fetch rollup node sync status: {:?}", err);
                     continue
                 }
-                if last_stored_block_number == 0
-                    || last_stored_block_number < sync_status.safe_l2
-                {
-                    last_stored_block_number = sync_status.safe_l2;
-                }
-                (last_stored_block_number, sync_status.unsafe_l2)
             };
+            if sync_status.head_l1.number == 0 {
+                tracing::warn!(target: "archon::channels", "Rollup node is not synced yet. Waiting for rollup node to sync...");
+                continue
+            }
+            last_stored_block = ChannelManager::load_unsafe_blocks(
+                &l2_node,
+                &state,
+                &sync_status,
+                last_stored_block,
+            )
+            .await;
+        }
+    }
+
+    /// Loads the unsafe L2 blocks of the [SyncStatus] into the [State],
+    /// returning the last block loaded.
+    ///
+    /// Loading resumes after `last_stored_block` and ends at the rollup node's unsafe head.
+    /// If the safe head moved past `last_stored_block`, the blocks up to the safe head are
+    /// already derived from L1, so the [State] is pruned up to it and loading resumes from it.
+    /// Blocks are fetched forward by number in chunks of [MAX_UNSAFE_BLOCKS_PER_CHUNK], and each
+    /// chunk is stored once its parent hashes link it to the blocks before it, so that progress
+    /// is kept if a later fetch fails. The last chunk must end at the unsafe head's hash.
+    ///
+    /// If a block cannot be fetched, or the chain does not extend the rollup node's safe or
+    /// unsafe head, loading stops and resumes on the next poll. The [State] is only reset if
+    /// the chain conflicts with a block loaded before.
+    pub async fn load_unsafe_blocks(
+        l2_node: &Provider<Http>,
+        state: &Mutex<State>,
+        sync_status: &SyncStatus,
+        last_stored_block: Option<BlockNumHash>,
+    ) -> Option<BlockNumHash> {
+        let safe_head = BlockNumHash {
+            hash: sync_status.safe_l2.hash,
+            number: sync_status.safe_l2.number,
+        };
+        let unsafe_head = BlockNumHash {
+            hash: sync_status.unsafe_l2.hash,
+            number: sync_status.unsafe_l2.number,
+        };
+        let (mut parent, mut stored) = match last_stored_block {
+            Some(block) if block.number >= safe_head.number => (block, true),
+            _ => (safe_head, false),
+        };
+        if stored && unsafe_head.number <= parent.number {
+            if unsafe_head.number == parent.number && unsafe_head.hash != parent.hash {
+                return ChannelManager::reset_on_l2_reorg(state, parent.number, safe_head)
+            }
+            return last_stored_block
+        }
+        let mut prune = last_stored_block.is_some() && !stored;
+        if prune && unsafe_head == safe_head {
+            return match ChannelManager::prune_to_safe_head(state, safe_head) {
+                true => Some(safe_head),
+                false => last_stored_block,
+            }
+        }
 
-            // Process the L2 blocks
-            for block_number in (start_block + 1)..=(end_block + 1) {
-                let block = match l2_node.get_block_with_txs(block_number).await {
-                    Ok(Some(block)) => block,
-                    _ => {
-                        tracing::error!(target: "archon::channels", "Failed to fetch L2 block");
-                        continue
+        let mut loaded = last_stored_block;
+        while parent.number < unsafe_head.number {
+            // Fetch the next chunk forward from the parent
+            let end = unsafe_head
+                .number
+                .min(parent.number + MAX_UNSAFE_BLOCKS_PER_CHUNK);
+            let mut blocks = Vec::new();
+            let mut hash = parent.hash;
+            for number in (parent.number + 1)..=end {
+                let block = match l2_node.get_block_with_txs(number).await {
+                    Ok(Some(block)) if block.number == Some(number.into()) => block,
+                    Ok(_) => {
+                        tracing::warn!(target: "archon::channels", "L2 block {} not found, retrying on the next poll", number);
+                        return loaded
+                    }
+                    Err(e) => {
+                        tracing::error!(target: "archon::channels", "Failed to fetch L2 block {}: {:?}", number, e);
+                        return loaded
                     }
                 };
-                match state.lock() {
-                    Ok(mut s) => match block.number {
-                        Some(num) => {
-                            last_stored_block_number = num.as_u64();
-                            s.add_block(block);
+                if block.parent_hash != hash {
+                    if stored {
+                        return ChannelManager::reset_on_l2_reorg(state, number, safe_head)
+                    }
+                    tracing::warn!(target: "archon::channels", "L2 node does not extend the rollup node's safe head {}, retrying on the next poll", safe_head.number);
+                    return loaded
+                }
+                hash = block.hash.unwrap_or_default();
+                blocks.push(block);
+            }
+            if end == unsafe_head.number && hash != unsafe_head.hash {
+                tracing::warn!(target: "archon::channels", "L2 node does not extend to the rollup node's unsafe head {}, retrying on the next poll", unsafe_head.number);
+                return loaded
+            }
+
+            // Store the chunk, which extends the loaded blocks
+            if prune {
+                if !ChannelManager::prune_to_safe_head(state, safe_head) {
+                    return loaded
+                }
+                prune = false;
+            }
+            for block in blocks {
+                let hash = block.hash.unwrap_or_default();
+                match ChannelManager::store_block(state, block) {
+                    Ok(number) => {
+                        tracing::debug!(target: "archon::channels", "Processed L2 block: {:?}", number);
+                        parent = BlockNumHash { hash, number };
+                        loaded = Some(parent);
+                        stored = true;
+                    }
+                    Err(e) => match e.downcast_ref::<ChannelManagerError>() {
+                        Some(ChannelManagerError::L2Reorg) => {
+                            return ChannelManager::reset_on_l2_reorg(
+                                state,
+                                parent.number + 1,
+                                safe_head,
+                            )
                         }
-                        None => {
-                            tracing::error!(target: "archon::channels", "Failed to fetch L2 block number");
-                            continue
+                        _ => {
+                            tracing::error!(target: "archon::channels", "Failed to store L2 block: {:?}", e);
+                            return loaded
                         }
                     },
-                    Err(_) => {
-                        tracing::error!(target: "archon::channels", "Failed to lock state");
-                        continue
-                    }
                 }
-                tracing::debug!(target: "archon::channels", "Processed L2 block: {:?}", last_stored_block_number);
+            }
+        }
+        loaded
+    }
+
+    /// Prunes the [State] up to the safe head, which moved past the loaded blocks.
+    ///
+    /// Returns whether the [State] was pruned.
+    fn prune_to_safe_head(state: &Mutex<State>, safe_head: BlockNumHash) -> bool {
+        tracing::info!(target: "archon::channels", "Safe head {} moved past the loaded blocks, pruning them", safe_head.number);
+        match state.lock() {
+            Ok(mut s) => {
+                s.prune(safe_head);
+                true
+            }
+            Err(_) => {
+                tracing::error!(target: "archon::channels", "Failed to lock state");
+                false
+            }
+        }
+    }
+
+    /// Resets the [State] after an L2 reorg at the given block,
+    /// so that loading resumes from the safe head.
+    fn reset_on_l2_reorg(
+        state: &Mutex<State>,
+        block_number: u64,
+        safe_head: BlockNumHash,
+    ) -> Option<BlockNumHash> {
+        L2_REORG_RESETS.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(target: "archon::channels", "L2 reorg detected at block {}, resetting to safe head {}", block_number, safe_head.number);
+        match state.lock() {
+            Ok(mut s) => s.reset(),
+            Err(_) => {
+                tracing::error!(target: "archon::channels", "Failed to lock state")
+            }
+        }
+        None
+    }
+
+    /// Adds an L2 block to the [State], returning its block number.
+    ///
+    /// Returns a [ChannelManagerError::L2Reorg] if the block does not extend the [State].
+    pub fn store_block(state: &Mutex<State>, block: Block<Transaction>) -> Result<u64> {
+        let number = block
+            .number
+            .ok_or(eyre::eyre!("Failed to fetch L2 block number"))?
+            .as_u64();
+        let update = state
+            .lock()
+            .map_err(|_| eyre::eyre!("Failed to lock state"))?
+            .add_block(block);
+        match update {
+            BlockUpdate::Added => Ok(number),
+            BlockUpdate::Reorg => Err(ChannelManagerError::L2Reorg.into()),
+            BlockUpdate::MissingBlockHash => {
+                Err(eyre::eyre!("L2 block {} is missing a hash", number))
             }
         }
     }
@@ -261,35 +892,64 @@ impl ChannelManager {
 
     /// resets all pending state back to an initialized but empty state.
     pub fn clear_pending_channels(&mut self) -> Result<()> {
+        self.blocks.clear();
+        self.channels.clear();
         self.pending_txs.clear();
         self.confirmed_txs.clear();
         Ok(())
     }
 
     /// Constructs a [PendingChannel].
+    ///
+    /// If a max channel duration is configured, the channel's duration deadline
+    /// is set relative to the latest L1 head.
     pub fn construct_pending_channel(&self) -> Result<PendingChannel> {
-        Err(ChannelManagerError::NotImplemented.into())
+        let pending = PendingChannel::new(self.channel_config.open_channel());
+        let max_duration = self.channel_config.max_channel_duration;
+        Ok(match self.l1_head {
+            Some(l1_head) if max_duration > 0 => {
+                pending.with_duration_deadline(l1_head + max_duration)
+            }
+            _ => pending,
+        })
     }
 }
 
 impl Stage for ChannelManager {
-    type Input = BlockId;
-    type Output = Bytes;
+    type Input = DriverEvent;
+    type Output = TaggedData;
 
     fn build(
         &mut self,
-        _pipeline: &mut Archon,
-        receiver: Option<Receiver<Pin<Box<BlockId>>>>,
-    ) -> Result<Receiver<Pin<Box<Bytes>>>> {
-        let (cm_sender, archon_receiver) = channel::<Pin<Box<Bytes>>>();
-        let (_archon_sender, cm_receiver) = channel::<Pin<Box<BlockId>>>();
+        pipeline: &mut Archon,
+        receiver: Option<Receiver<Pin<Box<DriverEvent>>>>,
+    ) -> Result<Receiver<Pin<Box<TaggedData>>>> {
+        let (cm_sender, archon_receiver) = channel::<Pin<Box<TaggedData>>>();
+        let (archon_sender, cm_receiver) = channel::<Pin<Box<DriverEvent>>>();
+        let (outcome_sender, outcome_receiver) =
+            channel::<Pin<Box<TransactionOutcome>>>();
         self.with_sender(cm_sender);
         self.with_receiver(cm_receiver);
+        self.with_outcome_receiver(outcome_receiver);
+        pipeline.with_outcome_sender(outcome_sender);
         self.receive_blocks(receiver);
+        self.with_channel_config(pipeline.config().channel_config());
+        self.with_nonce_manager(pipeline.nonce_manager());
+        self.with_cancellation(pipeline.cancellation());
+        pipeline.with_channel_manager_sender(archon_sender);
+        pipeline.with_channel_manager(std::mem::take(self));
         Ok(archon_receiver)
     }
 }
 
+/// Returns the block number of a [BlockId], if it is given by number.
+fn block_number(block_id: &BlockId) -> Option<u64> {
+    match block_id {
+        BlockId::Number(BlockNumber::Number(number)) => Some(number.as_u64()),
+        _ => None,
+    }
+}
+
 /// TransactionID is an opaque identifier for a transaction.
 /// It's internal fields should not be inspected after creation & are subject to change.
 /// This ID must be trivially comparable & work as a map key.
@@ -301,6 +961,34 @@ pub struct TransactionID {
     frame_number: u64,
 }
 
+impl TransactionID {
+    /// Constructs a new [TransactionID] for the given frame of a channel.
+    pub fn new(channel_id: ChannelId, frame_number: u64) -> Self {
+        Self {
+            channel_id: hex::encode(channel_id),
+            frame_number,
+        }
+    }
+
+    /// Constructs a new [TransactionID] for the output proposal of an L2 block.
+    pub fn output(l2_block_number: u64) -> Self {
+        Self {
+            channel_id: String::from("output"),
+            frame_number: l2_block_number,
+        }
+    }
+
+    /// Returns the hex-encoded id of the channel the frame belongs to.
+    pub fn channel_id(&self) -> &str {
+        &self.channel_id
+    }
+
+    /// Returns the number of the frame within its channel.
+    pub fn frame_number(&self) -> u64 {
+        self.frame_number
+    }
+}
+
 impl Default for TransactionID {
     fn default() -> Self {
         Self {
@@ -324,3 +1012,20 @@ pub struct TaggedData {
     /// The associated transaction id
     id: TransactionID,
 }
+
+impl TaggedData {
+    /// Constructs new [TaggedData].
+    pub fn new(data: Bytes, id: TransactionID) -> Self {
+        Self { data, id }
+    }
+
+    /// Returns the tagged data.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Returns the associated [TransactionID].
+    pub fn id(&self) -> &TransactionID {
+        &self.id
+    }
+}
@@ -8,21 +8,31 @@ use std::{
     time::Duration,
 };
 
-use bytes::Bytes;
-use ethers_core::types::{
-    BlockId,
-    TransactionReceipt,
-};
 use eyre::Result;
 use tokio::task::JoinHandle;
 
 use crate::{
-    channels::ChannelManager,
+    channels::{
+        ChannelManager,
+        TaggedData,
+    },
     config::Config,
-    driver::Driver,
+    driver::{
+        Driver,
+        DriverEvent,
+    },
+    errors::ConfigError,
     metrics::Metrics,
+    nonce::NonceManager,
     pipeline_builder::PipelineBuilder,
-    transactions::TransactionManager,
+    proposer::Proposer,
+    rollup::RollupNode,
+    transactions::{
+        Cancellation,
+        DataAvailabilityType,
+        TransactionManager,
+        TransactionOutcome,
+    },
 };
 
 /// Archon
@@ -32,11 +42,18 @@ use crate::{
 /// Archon batching stages are broken up into actors, spawned in separate [std::thread::Thread]s.
 ///
 /// The first actor is the [Driver]. The [Driver] polls an L1 [ethers_providers::Provider] for the
-/// latest block on a given interval. It takes the [ethers_core::types::Block] and constructs an
-/// [ethers_core::types::BlockId] which it then sends back to [Archon].
+/// latest block on a given interval. It checks the [ethers_core::types::Block] for L1 reorgs and
+/// constructs a [DriverEvent] which it then sends back to [Archon].
 ///
-/// When [Archon] receives a [ethers_core::types::BlockId] from the [Driver], it passes it along to
+/// When [Archon] receives a [DriverEvent] from the [Driver], it passes it along to
 /// the [ChannelManager].
+///
+/// The [TransactionOutcome]s of the frames submitted by the [TransactionManager] are passed back
+/// to the [ChannelManager], which requeues failed frames.
+///
+/// If a DisputeGameFactory or an L2OutputOracle is configured, a [Proposer] proposes L2 outputs
+/// alongside the batch submission pipeline, through its own [TransactionManager]. The
+/// DisputeGameFactory takes precedence if both are configured.
 #[derive(Debug, Default)]
 pub struct Archon {
     /// The inner [Config], used to configure [Archon]'s parameters
@@ -46,7 +63,7 @@ pub struct Archon {
     /// A join handle on the driver
     driver_handle: Option<JoinHandle<Result<()>>>,
     /// Driver receiver
-    driver_receiver: Option<Receiver<Pin<Box<BlockId>>>>,
+    driver_receiver: Option<Receiver<Pin<Box<DriverEvent>>>>,
     /// The inner [ChannelManager]
     channel_manager: Option<ChannelManager>,
     /// A join handle on the [ChannelManager]
@@ -54,15 +71,27 @@ pub struct Archon {
     /// A join handle on the [ChannelManager] block processor
     channel_manager_block_handle: Option<JoinHandle<Result<()>>>,
     /// The internal [ChannelManager] sender
-    channel_manager_sender: Option<Sender<Pin<Box<BlockId>>>>,
+    channel_manager_sender: Option<Sender<Pin<Box<DriverEvent>>>>,
+    /// Channel manager receiver
+    channel_manager_receiver: Option<Receiver<Pin<Box<TaggedData>>>>,
+    /// The internal sender of [TransactionOutcome]s to the [ChannelManager]
+    outcome_sender: Option<Sender<Pin<Box<TransactionOutcome>>>>,
     /// The inner [TransactionManager]
     tx_manager: Option<TransactionManager>,
     /// A join handle on the [TransactionManager]
     tx_manager_handle: Option<JoinHandle<Result<()>>>,
     /// The internal [TransactionManager] sender
-    tx_manager_sender: Option<Sender<Pin<Box<Bytes>>>>,
+    tx_manager_sender: Option<Sender<Pin<Box<TaggedData>>>>,
     /// Transaction manager receiver
-    tx_manager_receiver: Option<Receiver<Pin<Box<TransactionReceipt>>>>,
+    tx_manager_receiver: Option<Receiver<Pin<Box<TransactionOutcome>>>>,
+    /// The [NonceManager] shared by the [ChannelManager] and [TransactionManager]
+    nonce_manager: NonceManager,
+    /// The [Cancellation] handle shared by the [ChannelManager] and [TransactionManager]
+    cancellation: Cancellation,
+    /// The inner [Proposer]
+    proposer: Option<Proposer>,
+    /// A join handle on the [Proposer]
+    proposer_handle: Option<JoinHandle<Result<()>>>,
     /// A metrics server for the [Archon] client
     metrics: Option<Metrics>,
 }
@@ -94,6 +123,12 @@ impl Archon {
         self
     }
 
+    /// Sets the [Proposer] instance on the [Archon] client
+    pub fn with_proposer(&mut self, proposer: Proposer) -> &mut Self {
+        self.proposer = Some(proposer);
+        self
+    }
+
     /// Sets a [Metrics] server on the [Archon] client
     pub fn with_metrics(&mut self, metrics: Metrics) -> &mut Self {
         self.metrics = Some(metrics);
@@ -105,10 +140,38 @@ impl Archon {
         &self.config
     }
 
+    /// Returns a handle on the [NonceManager] shared by the pipeline stages
+    pub fn nonce_manager(&self) -> NonceManager {
+        self.nonce_manager.clone()
+    }
+
+    /// Returns a handle on the [Cancellation] of transactions in flight, shared by the pipeline stages
+    pub fn cancellation(&self) -> Cancellation {
+        self.cancellation.clone()
+    }
+
+    /// Sets the internal [ChannelManager] sender
+    pub fn with_channel_manager_sender(
+        &mut self,
+        sender: Sender<Pin<Box<DriverEvent>>>,
+    ) -> &mut Self {
+        self.channel_manager_sender = Some(sender);
+        self
+    }
+
+    /// Sets the internal [TransactionOutcome] sender to the [ChannelManager]
+    pub fn with_outcome_sender(
+        &mut self,
+        sender: Sender<Pin<Box<TransactionOutcome>>>,
+    ) -> &mut Self {
+        self.outcome_sender = Some(sender);
+        self
+    }
+
     /// Sets the internal [TransactionManager] sender
     pub fn with_tx_manager_sender(
         &mut self,
-        sender: Sender<Pin<Box<Bytes>>>,
+        sender: Sender<Pin<Box<TaggedData>>>,
     ) -> &mut Self {
         self.tx_manager_sender = Some(sender);
         self
@@ -118,20 +181,20 @@ impl Archon {
     /// Opens up a [std::sync::mpsc::channel] with the created [Driver].
     /// Spawns the [Driver] in a new [std::thread::Thread].
     ///
+    /// A [Driver] built by the [PipelineBuilder] is spawned as is.
+    ///
     /// Returns a [JoinHandle] to the spawned [Driver] if successfully spawed.
     pub fn spawn_driver(&mut self) -> Result<()> {
-        let (sender, receiver) = mpsc::channel::<Pin<Box<BlockId>>>();
-        self.driver_receiver = Some(receiver);
-        let driver = self.driver.take();
-        let mut driver = if let Some(d) = driver {
-            d
-        } else {
-            // Construct an L1 client
-            let l1_client = self.config.get_l1_client()?;
-            let poll_interval = self.config.polling_interval;
-            Driver::new(l1_client, poll_interval, None)
+        let driver = match self.driver.take() {
+            Some(d) => d,
+            None => {
+                let receiver = self.build_driver()?;
+                self.driver_receiver = Some(receiver);
+                self.driver
+                    .take()
+                    .ok_or(eyre::eyre!("Failed to build driver"))?
+            }
         };
-        driver.with_channel(sender);
         self.driver_handle = Some(
             driver
                 .spawn()
@@ -142,19 +205,24 @@ impl Archon {
 
     /// Instantiates a [ChannelManager] if needed.
     /// Opens up two [std::sync::mpsc::channel]s with the created [ChannelManager].
-    /// One to send [BlockId]s to the [ChannelManager], and one to receive [Bytes].
+    /// One to send [DriverEvent]s to the [ChannelManager], and one to receive [TaggedData].
     /// Spawns the [ChannelManager] in a new [std::thread::Thread].
     ///
+    /// A [ChannelManager] built by the [PipelineBuilder] is spawned as is.
+    ///
     /// Returns a [JoinHandle] to the spawned [ChannelManager] if successfully spawed.
     pub fn spawn_channel_manager(&mut self) -> Result<()> {
-        let (cm_sender, _) = mpsc::channel::<Pin<Box<Bytes>>>();
-        let (archon_sender, cm_receiver) = mpsc::channel::<Pin<Box<BlockId>>>();
-        self.channel_manager_sender = Some(archon_sender);
-        // self.channel_manager_receiver = Some(archon_receiver);
-        let channel_manager = self.channel_manager.take();
-        let mut channel_manager = channel_manager.unwrap_or_default();
-        channel_manager.with_sender(cm_sender);
-        channel_manager.with_receiver(cm_receiver);
+        let mut channel_manager = match self.channel_manager.take() {
+            Some(cm) => cm,
+            None => {
+                let block_recv = self.driver_receiver.take();
+                let (_, bytes_recv) = self.build_channel_manager(block_recv)?;
+                self.channel_manager_receiver = Some(bytes_recv);
+                self.channel_manager
+                    .take()
+                    .ok_or(eyre::eyre!("Failed to build channel manager"))?
+            }
+        };
         let poll_interval = self
             .config
             .polling_interval
@@ -180,38 +248,100 @@ impl Archon {
 
     /// Instantiates a [TransactionManager] if needed.
     /// Opens up two [std::sync::mpsc::channel]s with the created [TransactionManager].
-    /// One to send [Bytes]s to the [TransactionManager], and one to receive [TransactionReceipt]s.
+    /// One to send [TaggedData] to the [TransactionManager], and one to receive [TransactionOutcome]s.
     /// Spawns the [TransactionManager] in a new [std::thread::Thread].
     ///
+    /// A [TransactionManager] built by the [PipelineBuilder] is spawned as is.
+    ///
     /// Returns a [JoinHandle] to the spawned [TransactionManager] if successfully spawed.
     pub fn spawn_transaction_manager(&mut self) -> Result<()> {
-        let (tx_mgr_sender, archon_receiver) =
-            mpsc::channel::<Pin<Box<TransactionReceipt>>>();
-        let (archon_sender, tx_mgr_receiver) = mpsc::channel::<Pin<Box<Bytes>>>();
-        self.tx_manager_sender = Some(archon_sender);
-        self.tx_manager_receiver = Some(archon_receiver);
-        let transaction_manager = self.tx_manager.take();
-        let mut transaction_manager =
-            transaction_manager.unwrap_or(TransactionManager::new(
-                Some(self.config.network.into()),
-                Some(self.config.batcher_inbox),
-                Some(self.config.proposer_address),
-                Some(self.config.batcher_private_key.clone()),
-                self.config.get_l1_client()?,
-            ));
-        transaction_manager.with_sender(tx_mgr_sender);
-        transaction_manager.with_receiver(tx_mgr_receiver);
-        self.tx_manager_handle = Some(
-            transaction_manager
+        let transaction_manager = match self.tx_manager.take() {
+            Some(tm) => tm,
+            None => {
+                let bytes_recv = self.channel_manager_receiver.take();
+                let (_, receipt_recv) = self.build_transaction_manager(bytes_recv)?;
+                self.tx_manager_receiver = Some(receipt_recv);
+                self.tx_manager
+                    .take()
+                    .ok_or(eyre::eyre!("Failed to build transaction manager"))?
+            }
+        };
+        self.tx_manager_handle =
+            Some(transaction_manager.spawn().map_err(|e| {
+                eyre::eyre!("Failed to spawn transaction manager: {}", e)
+            })?);
+        Ok(())
+    }
+
+    /// Instantiates a [Proposer] if needed, and spawns it with its own [TransactionManager].
+    ///
+    /// No [Proposer] is spawned if none is set and neither a DisputeGameFactory nor an
+    /// L2OutputOracle is configured.
+    pub fn spawn_proposer(&mut self) -> Result<()> {
+        let proposer = match self.proposer.take() {
+            Some(proposer) => proposer,
+            None => match self.build_proposer()? {
+                Some(proposer) => proposer,
+                None => return Ok(()),
+            },
+        };
+        self.proposer_handle = Some(
+            proposer
                 .spawn()
-                .map_err(|_| eyre::eyre!("Failed to spawn transaction manager"))?,
+                .map_err(|e| eyre::eyre!("Failed to spawn proposer: {}", e))?,
         );
         Ok(())
     }
 
+    /// Builds a new [Proposer] instance, if a DisputeGameFactory or an L2OutputOracle is
+    /// configured. The DisputeGameFactory takes precedence if both are configured.
+    ///
+    /// The [Proposer]'s [TransactionManager] submits transactions from the proposer account,
+    /// with its own nonces. If the proposer account is also the batcher account, as with the
+    /// default keys, the [NonceManager] of the batch submission pipeline is shared instead,
+    /// so that both never publish transactions with the same nonce.
+    ///
+    /// L1 does not accept calldata transactions pending alongside blob transactions from the
+    /// same account, so a shared account is refused if the batcher may post blobs.
+    pub fn build_proposer(&self) -> Result<Option<Proposer>> {
+        let proposer_config = match self.config.proposer_config() {
+            Some(proposer_config) => proposer_config,
+            None => return Ok(None),
+        };
+        let shared_account = self.config.proposer_address == self.config.batcher_address;
+        if shared_account
+            && matches!(
+                self.config.data_availability_type,
+                DataAvailabilityType::Blobs | DataAvailabilityType::Auto
+            )
+        {
+            return Err(ConfigError::SharedBlobAccount(self.config.proposer_address).into())
+        }
+        let l1_client = self.config.get_l1_client()?;
+        let mut tx_manager = TransactionManager::new(
+            Some(self.config.l1_chain_id()),
+            Some(proposer_config.target.address()),
+            Some(self.config.proposer_address),
+            Some(self.config.proposer_private_key.clone()),
+            l1_client.clone(),
+        );
+        tx_manager.with_transaction_config(self.config.proposer_transaction_config());
+        if shared_account {
+            tracing::warn!(target: "archon", "Proposer and batcher share account {:?}, sharing their nonces", self.config.proposer_address);
+            tx_manager.with_nonce_manager(self.nonce_manager());
+        }
+        let mut proposer = Proposer::new(
+            proposer_config,
+            RollupNode::new(&self.config.rollup_node_rpc_url)?,
+            l1_client,
+        );
+        proposer.with_transaction_manager(tx_manager);
+        Ok(Some(proposer))
+    }
+
     /// Builds a new [Driver] instance.
-    pub fn build_driver(&mut self) -> Result<Receiver<Pin<Box<BlockId>>>> {
-        let (sender, receiver) = mpsc::channel::<Pin<Box<BlockId>>>();
+    pub fn build_driver(&mut self) -> Result<Receiver<Pin<Box<DriverEvent>>>> {
+        let (sender, receiver) = mpsc::channel::<Pin<Box<DriverEvent>>>();
         let driver = self.driver.take();
         let mut driver = if let Some(d) = driver {
             d
@@ -230,15 +360,27 @@ impl Archon {
     /// Builds a new [ChannelManager] instance.
     pub fn build_channel_manager(
         &mut self,
-        block_recv: Option<Receiver<Pin<Box<BlockId>>>>,
-    ) -> Result<(Sender<Pin<Box<BlockId>>>, Receiver<Pin<Box<Bytes>>>)> {
-        let (cm_sender, archon_receiver) = mpsc::channel::<Pin<Box<Bytes>>>();
-        let (archon_sender, cm_receiver) = mpsc::channel::<Pin<Box<BlockId>>>();
+        block_recv: Option<Receiver<Pin<Box<DriverEvent>>>>,
+    ) -> Result<(
+        Sender<Pin<Box<DriverEvent>>>,
+        Receiver<Pin<Box<TaggedData>>>,
+    )> {
+        let (cm_sender, archon_receiver) = mpsc::channel::<Pin<Box<TaggedData>>>();
+        let (archon_sender, cm_receiver) = mpsc::channel::<Pin<Box<DriverEvent>>>();
+        let (outcome_sender, outcome_receiver) =
+            mpsc::channel::<Pin<Box<TransactionOutcome>>>();
         let channel_manager = self.channel_manager.take();
         let mut channel_manager = channel_manager.unwrap_or_default();
         channel_manager.with_sender(cm_sender);
         channel_manager.with_receiver(cm_receiver);
+        channel_manager.with_outcome_receiver(outcome_receiver);
+        self.outcome_sender = Some(outcome_sender);
         channel_manager.receive_blocks(block_recv);
+        channel_manager.with_channel_config(self.config.channel_config());
+        channel_manager.with_nonce_manager(self.nonce_manager());
+        channel_manager.with_cancellation(self.cancellation());
+        self.channel_manager_sender = Some(archon_sender.clone());
+        self.channel_manager = Some(channel_manager);
         Ok((archon_sender, archon_receiver))
     }
 
@@ -246,28 +388,32 @@ impl Archon {
     /// Builds a new [TransactionManager] instance.
     pub fn build_transaction_manager(
         &mut self,
-        bytes_recv: Option<Receiver<Pin<Box<Bytes>>>>,
+        bytes_recv: Option<Receiver<Pin<Box<TaggedData>>>>,
     ) -> Result<(
-        Sender<Pin<Box<Bytes>>>,
-        Receiver<Pin<Box<TransactionReceipt>>>,
+        Sender<Pin<Box<TaggedData>>>,
+        Receiver<Pin<Box<TransactionOutcome>>>,
     )> {
-        let (archon_sender, tx_mgr_receiver) = mpsc::channel::<Pin<Box<Bytes>>>();
+        let (archon_sender, tx_mgr_receiver) = mpsc::channel::<Pin<Box<TaggedData>>>();
         let (tx_mgr_sender, archon_receiver) =
-            mpsc::channel::<Pin<Box<TransactionReceipt>>>();
+            mpsc::channel::<Pin<Box<TransactionOutcome>>>();
         self.tx_manager_sender = Some(archon_sender.clone());
         // self.tx_manager_receiver = Some(archon_receiver.clone());
         let transaction_manager = self.tx_manager.take();
         let mut transaction_manager =
             transaction_manager.unwrap_or(TransactionManager::new(
-                Some(self.config.network.into()),
+                Some(self.config.l1_chain_id()),
                 Some(self.config.batcher_inbox),
-                Some(self.config.proposer_address),
+                Some(self.config.batcher_address),
                 Some(self.config.batcher_private_key.clone()),
                 self.config.get_l1_client()?,
             ));
+        transaction_manager.with_transaction_config(self.config.transaction_config());
+        transaction_manager.with_nonce_manager(self.nonce_manager());
+        transaction_manager.with_cancellation(self.cancellation());
         transaction_manager.with_sender(tx_mgr_sender);
         transaction_manager.with_receiver(tx_mgr_receiver);
         transaction_manager.receive_bytes(bytes_recv);
+        self.tx_manager = Some(transaction_manager);
         Ok((archon_sender, archon_receiver))
     }
 
@@ -285,6 +431,11 @@ impl Archon {
         tracing::info!(target: "archon", "Serving archon metrics");
         self.metrics = Some(Metrics::new());
 
+        tracing::info!(target: "archon", "Checking config against the rollup node");
+        let rollup_node = RollupNode::new(&self.config.rollup_node_rpc_url)?;
+        let rollup_config = rollup_node.rollup_config().await?;
+        self.config.apply_rollup_config(&rollup_config)?;
+
         tracing::info!(target: "archon", "Building batch submission pipeline");
         // let block_recv = self.build_driver()?;
         // let (_, bytes_recv) = self.build_channel_manager(Some(block_recv))?;
@@ -300,11 +451,17 @@ impl Archon {
         self.spawn_driver()?;
         self.spawn_channel_manager()?;
         self.spawn_transaction_manager()?;
+        self.spawn_proposer()?;
 
-        // Receipt transactions
+        // Pass transaction outcomes back to the channel manager
         let receipt_recv = receipt_recv;
-        for receipt in receipt_recv {
-            tracing::info!(target: "archon", "Received receipt: {:?}", receipt);
+        for outcome in receipt_recv {
+            tracing::info!(target: "archon", "Received transaction outcome: {:?}", outcome);
+            if let Some(sender) = &self.outcome_sender {
+                if sender.send(outcome).is_err() {
+                    tracing::error!(target: "archon", "Failed to pass transaction outcome to channel manager");
+                }
+            }
         }
 
         tracing::info!(target: "archon", "Serving metrics on batch submission");
@@ -8,6 +8,7 @@ use ethers_core::types::{
     Address,
     Chain,
     H256,
+    U256,
 };
 use ethers_providers::{
     Http,
@@ -20,8 +21,37 @@ use serde::{
 };
 
 use crate::{
+    builder::{
+        ChannelConfig,
+        DEFAULT_APPROX_COMPRESSION_RATIO,
+        DEFAULT_CHANNEL_TIMEOUT,
+        DEFAULT_MAX_FRAME_SIZE,
+        DEFAULT_TARGET_FRAME_SIZE,
+    },
     errors::ConfigError,
     extract_env,
+    proposer::{
+        DisputeGameConfig,
+        ProposalTarget,
+        ProposerConfig,
+        DEFAULT_GAME_BOND,
+        DEFAULT_PROPOSAL_INTERVAL,
+        DEFAULT_SUBMISSION_INTERVAL,
+    },
+    rollup::RollupConfig,
+    span_batch::BatchType,
+    transactions::{
+        gwei,
+        DataAvailabilityType,
+        TransactionConfig,
+        DEFAULT_BASE_FEE_MULTIPLIER,
+        DEFAULT_FEE_BUMP_INTERVAL,
+        DEFAULT_FEE_CEILING_GWEI,
+        DEFAULT_MAX_PENDING_TRANSACTIONS,
+        DEFAULT_NUM_CONFIRMATIONS,
+        DEFAULT_RECEIPT_QUERY_INTERVAL,
+        DEFAULT_RESUBMISSION_TIMEOUT,
+    },
 };
 
 /// A system configuration
@@ -53,6 +83,58 @@ pub struct Config {
     pub batcher_inbox: Address,
     /// The driver's polling interval.
     pub polling_interval: Option<Duration>,
+    /// The maximum size of a frame, in bytes.
+    pub max_frame_size: usize,
+    /// The target size of a frame, in bytes.
+    pub target_frame_size: usize,
+    /// The target number of frames per channel.
+    pub target_num_frames: usize,
+    /// The approximate compression ratio of channel data.
+    pub approx_compression_ratio: f64,
+    /// The channel timeout of the rollup, in L1 blocks, until Granite.
+    pub channel_timeout: u64,
+    /// The L2 timestamp of the Granite hardfork, if scheduled.
+    pub granite_time: Option<u64>,
+    /// The maximum number of L1 blocks a channel is kept open, or 0 to disable.
+    pub max_channel_duration: u64,
+    /// The type of batches to write into channels.
+    pub batch_type: BatchType,
+    /// The L2 genesis timestamp, used to encode span batches.
+    pub l2_genesis_timestamp: u64,
+    /// The multiplier applied to the L1 base fee to derive the max fee per gas.
+    pub base_fee_multiplier: u64,
+    /// The interval after which the fees of a pending transaction are bumped.
+    pub fee_bump_interval: Duration,
+    /// The ceiling of the max fee per gas, in gwei.
+    pub fee_ceiling_gwei: u64,
+    /// The maximum number of batcher transactions pending on L1 at once.
+    pub max_pending_transactions: usize,
+    /// The number of L1 confirmations to wait for before a transaction is confirmed.
+    pub num_confirmations: u64,
+    /// The interval at which receipts of pending transactions are queried.
+    pub receipt_query_interval: Duration,
+    /// The time after which a transaction that was never mined is given up on.
+    pub resubmission_timeout: Duration,
+    /// Whether in-flight transactions are cancelled on shutdown or after an L2 reorg reset.
+    pub cancel_pending_transactions: bool,
+    /// How frames are made available on L1.
+    pub data_availability_type: DataAvailabilityType,
+    /// The url of the alt-DA server frames are stored on, if any.
+    pub da_server: Option<String>,
+    /// The L2OutputOracle outputs are proposed to, or `None` to disable proposing.
+    pub l2_output_oracle: Option<Address>,
+    /// The interval at which new outputs to propose are polled for.
+    pub proposal_interval: Duration,
+    /// Whether outputs up to the safe L2 head are proposed, instead of up to the finalized L2 head.
+    pub allow_non_finalized: bool,
+    /// The DisputeGameFactory outputs are claimed through, instead of the L2OutputOracle.
+    pub dispute_game_factory: Option<Address>,
+    /// The type of the dispute games created.
+    pub game_type: u32,
+    /// The bond posted with every dispute game, in wei.
+    pub game_bond: U256,
+    /// The interval between L2 blocks claimed in dispute games.
+    pub submission_interval: u64,
 }
 
 impl Default for Config {
@@ -73,7 +155,7 @@ impl Default for Config {
             )
             .unwrap(),
             batcher_address: Address::from_str(
-                "0x7431310e026B69BFC676C0013E12A1A11411EEc9",
+                "0x87A159604e2f18B01a080F672ee011F39777E640",
             )
             .unwrap(),
             batcher_private_key: String::from(
@@ -89,6 +171,32 @@ impl Default for Config {
             )
             .unwrap(),
             polling_interval: Some(Duration::from_secs(5)),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            target_frame_size: DEFAULT_TARGET_FRAME_SIZE,
+            target_num_frames: 1,
+            approx_compression_ratio: DEFAULT_APPROX_COMPRESSION_RATIO,
+            channel_timeout: DEFAULT_CHANNEL_TIMEOUT,
+            granite_time: None,
+            max_channel_duration: 0,
+            batch_type: BatchType::Singular,
+            l2_genesis_timestamp: 1686068903,
+            base_fee_multiplier: DEFAULT_BASE_FEE_MULTIPLIER,
+            fee_bump_interval: DEFAULT_FEE_BUMP_INTERVAL,
+            fee_ceiling_gwei: DEFAULT_FEE_CEILING_GWEI,
+            max_pending_transactions: DEFAULT_MAX_PENDING_TRANSACTIONS,
+            num_confirmations: DEFAULT_NUM_CONFIRMATIONS,
+            receipt_query_interval: DEFAULT_RECEIPT_QUERY_INTERVAL,
+            resubmission_timeout: DEFAULT_RESUBMISSION_TIMEOUT,
+            cancel_pending_transactions: false,
+            data_availability_type: DataAvailabilityType::Calldata,
+            da_server: None,
+            l2_output_oracle: None,
+            proposal_interval: DEFAULT_PROPOSAL_INTERVAL,
+            allow_non_finalized: false,
+            dispute_game_factory: None,
+            game_type: 0,
+            game_bond: U256::from(DEFAULT_GAME_BOND),
+            submission_interval: DEFAULT_SUBMISSION_INTERVAL,
         }
     }
 }
@@ -114,6 +222,127 @@ impl Config {
         self.proposer_address
     }
 
+    /// Constructs the [ChannelConfig] used to build channels.
+    pub fn channel_config(&self) -> ChannelConfig {
+        ChannelConfig {
+            max_frame_size: self.max_frame_size,
+            target_frame_size: self.target_frame_size,
+            target_num_frames: self.target_num_frames,
+            approx_compression_ratio: self.approx_compression_ratio,
+            channel_timeout: self.channel_timeout,
+            granite_time: self.granite_time,
+            max_channel_duration: self.max_channel_duration,
+            batch_type: self.batch_type,
+            l2_genesis_timestamp: self.l2_genesis_timestamp,
+            l2_chain_id: self.l2_chain_id(),
+        }
+    }
+
+    /// Constructs the [TransactionConfig] used to submit transactions.
+    pub fn transaction_config(&self) -> TransactionConfig {
+        TransactionConfig {
+            base_fee_multiplier: self.base_fee_multiplier,
+            fee_bump_interval: self.fee_bump_interval,
+            fee_ceiling: gwei(self.fee_ceiling_gwei),
+            max_pending_transactions: self.max_pending_transactions,
+            num_confirmations: self.num_confirmations,
+            receipt_query_interval: self.receipt_query_interval,
+            resubmission_timeout: self.resubmission_timeout,
+            cancel_pending_transactions: self.cancel_pending_transactions,
+            data_availability_type: self.data_availability_type,
+            da_server: self.da_server.clone(),
+            estimate_gas: false,
+            value: U256::zero(),
+        }
+    }
+
+    /// Constructs the [ProposerConfig] used to propose outputs, if proposing is enabled.
+    ///
+    /// A configured DisputeGameFactory takes precedence over the L2OutputOracle.
+    pub fn proposer_config(&self) -> Option<ProposerConfig> {
+        let target = match (self.dispute_game_factory, self.l2_output_oracle) {
+            (Some(factory), _) => ProposalTarget::DisputeGameFactory(DisputeGameConfig {
+                factory,
+                game_type: self.game_type,
+                bond: self.game_bond,
+                submission_interval: self.submission_interval,
+            }),
+            (None, Some(l2_output_oracle)) => {
+                ProposalTarget::L2OutputOracle(l2_output_oracle)
+            }
+            (None, None) => return None,
+        };
+        Some(ProposerConfig {
+            target,
+            poll_interval: self.proposal_interval,
+            allow_non_finalized: self.allow_non_finalized,
+        })
+    }
+
+    /// Constructs the [TransactionConfig] used to submit output proposals.
+    ///
+    /// Proposals are always posted as calldata, with an estimated gas limit.
+    /// Dispute games are created with the game bond.
+    pub fn proposer_transaction_config(&self) -> TransactionConfig {
+        let value = match self.dispute_game_factory {
+            Some(_) => self.game_bond,
+            None => U256::zero(),
+        };
+        TransactionConfig {
+            data_availability_type: DataAvailabilityType::Calldata,
+            da_server: None,
+            estimate_gas: true,
+            value,
+            ..self.transaction_config()
+        }
+    }
+
+    /// Checks the [Config] against the [RollupConfig] of the rollup node, and sources
+    /// the channel timeout and the Granite activation from it, so that the timeout of
+    /// each channel follows the timestamps of its L2 blocks.
+    ///
+    /// The batcher inbox and the L1 and L2 chain ids must match, or else batches would be
+    /// submitted where the rollup node does not derive them from.
+    pub fn apply_rollup_config(&mut self, rollup_config: &RollupConfig) -> Result<()> {
+        if self.batcher_inbox != rollup_config.batch_inbox_address {
+            return Err(ConfigError::BatchInboxMismatch(
+                self.batcher_inbox,
+                rollup_config.batch_inbox_address,
+            )
+            .into())
+        }
+        if self.l1_chain_id() != rollup_config.l1_chain_id {
+            return Err(ConfigError::L1ChainIdMismatch(
+                self.l1_chain_id(),
+                rollup_config.l1_chain_id,
+            )
+            .into())
+        }
+        if self.l2_chain_id() != rollup_config.l2_chain_id {
+            return Err(ConfigError::L2ChainIdMismatch(
+                self.l2_chain_id(),
+                rollup_config.l2_chain_id,
+            )
+            .into())
+        }
+        if self.channel_timeout != rollup_config.channel_timeout {
+            tracing::info!(target: "archon::config", "Using the rollup node's channel timeout of {} L1 blocks", rollup_config.channel_timeout);
+        }
+        self.channel_timeout = rollup_config.channel_timeout;
+        self.granite_time = rollup_config.granite_time;
+        Ok(())
+    }
+
+    /// Returns the chain id of the L1 that batches are submitted to.
+    pub fn l1_chain_id(&self) -> u64 {
+        self.data_availability_layer.into()
+    }
+
+    /// Returns the chain id of the L2 that batches are submitted for.
+    pub fn l2_chain_id(&self) -> u64 {
+        self.network.into()
+    }
+
     /// Constructs an L1 provider
     pub fn get_l1_client(&self) -> Result<Provider<Http>> {
         Ok(Provider::<Http>::try_from(&self.l1_client_rpc_url)
@@ -153,7 +382,7 @@ pub struct Cli {
         default_value = "0x4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72"
     )]
     proposer_private_key: String,
-    /// The proposer public address.
+    /// The proposer public address. Must differ from the batcher's if the batcher posts blobs.
     #[clap(
         short = 'a',
         long,
@@ -199,6 +428,82 @@ pub struct Cli {
     /// The driver's polling interval.
     #[clap(short = 'i', long, default_value = "5")]
     polling_interval: u64,
+    /// The maximum size of a frame, in bytes.
+    #[clap(long, default_value = "120000")]
+    max_frame_size: usize,
+    /// The target size of a frame, in bytes.
+    #[clap(long, default_value = "100000")]
+    target_frame_size: usize,
+    /// The target number of frames per channel.
+    #[clap(long, default_value = "1")]
+    target_num_frames: usize,
+    /// The approximate compression ratio of channel data.
+    #[clap(long, default_value = "0.4")]
+    approx_compression_ratio: f64,
+    /// The channel timeout of the rollup, in L1 blocks. Replaced by the rollup node's on startup.
+    #[clap(long, default_value = "300")]
+    channel_timeout: u64,
+    /// The maximum number of L1 blocks a channel is kept open, or 0 to disable.
+    #[clap(long, default_value = "0")]
+    max_channel_duration: u64,
+    /// The type of batches to submit, either "singular" or "span".
+    #[clap(long, default_value = "singular")]
+    batch_type: String,
+    /// The L2 genesis timestamp, used to encode span batches.
+    #[clap(long, default_value = "1686068903")]
+    l2_genesis_timestamp: u64,
+    /// The multiplier applied to the L1 base fee to derive the max fee per gas.
+    #[clap(long, default_value = "2")]
+    base_fee_multiplier: u64,
+    /// The interval after which the fees of a pending transaction are bumped, in seconds.
+    #[clap(long, default_value = "60")]
+    fee_bump_interval: u64,
+    /// The ceiling of the max fee per gas, in gwei.
+    #[clap(long, default_value = "500")]
+    fee_ceiling_gwei: u64,
+    /// The maximum number of batcher transactions pending on L1 at once.
+    #[clap(long, default_value = "1")]
+    max_pending_transactions: usize,
+    /// The number of L1 confirmations to wait for before a transaction is confirmed.
+    #[clap(long, default_value = "6")]
+    num_confirmations: u64,
+    /// The interval at which receipts of pending transactions are queried, in seconds.
+    #[clap(long, default_value = "12")]
+    receipt_query_interval: u64,
+    /// The time after which a transaction that was never mined is given up on, in seconds.
+    #[clap(long, default_value = "600")]
+    resubmission_timeout: u64,
+    /// Cancel in-flight transactions on shutdown or after an L2 reorg reset.
+    #[clap(long)]
+    cancel_pending_transactions: bool,
+    /// How frames are made available on L1, either "calldata", "blobs", "auto" or "alt-da".
+    #[clap(long, default_value = "calldata")]
+    data_availability_type: String,
+    /// The url of the alt-DA server frames are stored on in "alt-da" mode.
+    #[clap(long)]
+    da_server: Option<String>,
+    /// The L2OutputOracle address to propose outputs to. Outputs are only proposed if set.
+    #[clap(long)]
+    l2_output_oracle: Option<String>,
+    /// The interval at which new outputs to propose are polled for, in seconds.
+    #[clap(long, default_value = "12")]
+    proposal_interval: u64,
+    /// Propose outputs up to the safe L2 head, instead of up to the finalized L2 head.
+    #[clap(long)]
+    allow_non_finalized: bool,
+    /// The DisputeGameFactory address to create dispute games through, instead of proposing
+    /// to the L2OutputOracle.
+    #[clap(long)]
+    dispute_game_factory: Option<String>,
+    /// The type of the dispute games created.
+    #[clap(long, default_value = "0")]
+    game_type: u32,
+    /// The bond posted with every dispute game, in wei.
+    #[clap(long, default_value = "80000000000000000")]
+    game_bond: String,
+    /// The interval between L2 blocks claimed in dispute games.
+    #[clap(long, default_value = "1800")]
+    submission_interval: u64,
 }
 
 impl Cli {
@@ -230,6 +535,39 @@ impl Cli {
             network: Chain::from_str(&self.network).unwrap().into(),
             polling_interval: Some(Duration::from_secs(self.polling_interval)),
             batcher_inbox: Address::from_str(&self.batcher_inbox).unwrap(),
+            max_frame_size: self.max_frame_size,
+            target_frame_size: self.target_frame_size,
+            target_num_frames: self.target_num_frames,
+            approx_compression_ratio: self.approx_compression_ratio,
+            channel_timeout: self.channel_timeout,
+            granite_time: None,
+            max_channel_duration: self.max_channel_duration,
+            batch_type: BatchType::from_str(&self.batch_type).unwrap(),
+            l2_genesis_timestamp: self.l2_genesis_timestamp,
+            base_fee_multiplier: self.base_fee_multiplier,
+            fee_bump_interval: Duration::from_secs(self.fee_bump_interval),
+            fee_ceiling_gwei: self.fee_ceiling_gwei,
+            max_pending_transactions: self.max_pending_transactions,
+            num_confirmations: self.num_confirmations,
+            receipt_query_interval: Duration::from_secs(self.receipt_query_interval),
+            resubmission_timeout: Duration::from_secs(self.resubmission_timeout),
+            cancel_pending_transactions: self.cancel_pending_transactions,
+            data_availability_type: DataAvailabilityType::from_str(
+                &self.data_availability_type,
+            )
+            .unwrap(),
+            da_server: self.da_server,
+            l2_output_oracle: self
+                .l2_output_oracle
+                .map(|address| Address::from_str(&address).unwrap()),
+            proposal_interval: Duration::from_secs(self.proposal_interval),
+            allow_non_finalized: self.allow_non_finalized,
+            dispute_game_factory: self
+                .dispute_game_factory
+                .map(|address| Address::from_str(&address).unwrap()),
+            game_type: self.game_type,
+            game_bond: U256::from_dec_str(&self.game_bond).unwrap(),
+            submission_interval: self.submission_interval,
         }
     }
 }
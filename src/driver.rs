@@ -1,7 +1,9 @@
 use crate::client::Archon;
 use ethers_core::types::{
+    Block,
     BlockId,
     BlockNumber,
+    H256,
 };
 use ethers_providers::{
     Http,
@@ -10,6 +12,7 @@ use ethers_providers::{
 };
 use eyre::Result;
 use std::{
+    collections::VecDeque,
     pin::Pin,
     sync::{
         mpsc::{
@@ -25,6 +28,164 @@ use std::{
 
 use crate::pipeline_builder::Stage;
 
+/// The number of recent L1 blocks the [Driver] keeps to detect reorgs.
+pub const L1_REORG_WINDOW: usize = 64;
+
+/// A reference to an L1 block.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct L1Head {
+    /// The block number
+    pub number: u64,
+    /// The block hash
+    pub hash: H256,
+    /// The parent block hash
+    pub parent_hash: H256,
+}
+
+impl L1Head {
+    /// Constructs an [L1Head] from an L1 [Block], if it has a number and hash.
+    pub fn from_block<T>(block: &Block<T>) -> Option<Self> {
+        Some(Self {
+            number: block.number?.as_u64(),
+            hash: block.hash?,
+            parent_hash: block.parent_hash,
+        })
+    }
+
+    /// Returns the [BlockId] of the L1 block, by number.
+    pub fn id(&self) -> BlockId {
+        BlockId::from(self.number)
+    }
+}
+
+/// An L1 chain event emitted by the [Driver].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriverEvent {
+    /// A new L1 head extending the known chain.
+    NewHead(L1Head),
+    /// The L1 chain reorged to a new head.
+    Reorg {
+        /// The number of the highest known L1 block that is still canonical.
+        common_ancestor: u64,
+        /// The new L1 head.
+        head: L1Head,
+    },
+}
+
+impl DriverEvent {
+    /// Returns the L1 head of the event.
+    pub fn head(&self) -> &L1Head {
+        match self {
+            Self::NewHead(head) | Self::Reorg { head, .. } => head,
+        }
+    }
+}
+
+/// A window of recent canonical L1 blocks, oldest first.
+#[derive(Debug, Clone)]
+pub struct L1Window {
+    /// The recent L1 blocks
+    blocks: VecDeque<L1Head>,
+    /// The maximum number of blocks kept
+    capacity: usize,
+}
+
+impl Default for L1Window {
+    fn default() -> Self {
+        Self::new(L1_REORG_WINDOW)
+    }
+}
+
+impl L1Window {
+    /// Constructs an empty [L1Window] keeping up to `capacity` blocks.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            blocks: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns the latest L1 block in the window.
+    pub fn tip(&self) -> Option<&L1Head> {
+        self.blocks.back()
+    }
+
+    /// Returns the oldest L1 block in the window.
+    pub fn oldest(&self) -> Option<&L1Head> {
+        self.blocks.front()
+    }
+
+    /// Returns the L1 block with the given number, if it is in the window.
+    pub fn get(&self, number: u64) -> Option<&L1Head> {
+        let oldest = self.oldest()?.number;
+        self.blocks.get(number.checked_sub(oldest)? as usize)
+    }
+
+    /// Returns whether the window contains the given L1 block.
+    pub fn contains(&self, head: &L1Head) -> bool {
+        self.get(head.number).is_some_and(|b| b.hash == head.hash)
+    }
+
+    /// Returns whether the parent of the given L1 block is in the window.
+    pub fn contains_parent(&self, head: &L1Head) -> bool {
+        head.number
+            .checked_sub(1)
+            .and_then(|number| self.get(number))
+            .is_some_and(|parent| parent.hash == head.parent_hash)
+    }
+
+    /// Pushes a new L1 block, dropping all blocks at or above its number.
+    pub fn push(&mut self, head: L1Head) {
+        while self.tip().is_some_and(|tip| tip.number >= head.number) {
+            self.blocks.pop_back();
+        }
+        if self.tip().is_some_and(|tip| tip.number + 1 != head.number) {
+            self.blocks.clear();
+        }
+        self.blocks.push_back(head);
+        while self.blocks.len() > self.capacity {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Pushes a chain of new L1 blocks, whose first block's parent is in the window,
+    /// and returns the resulting [DriverEvent].
+    ///
+    /// If the head is already in the window below its tip, L1 reorged back to a shorter
+    /// chain: the blocks above the head are dropped and the head is the common ancestor.
+    /// The chain must be ordered oldest first.
+    pub fn extend(&mut self, chain: Vec<L1Head>) -> Option<DriverEvent> {
+        let head = *chain.last()?;
+        if self.contains(&head) && self.tip().is_some_and(|tip| tip.number > head.number)
+        {
+            while self.tip().is_some_and(|tip| tip.number > head.number) {
+                self.blocks.pop_back();
+            }
+            return Some(DriverEvent::Reorg {
+                common_ancestor: head.number,
+                head,
+            })
+        }
+        let first = chain.first()?;
+        let ancestor = first.number.saturating_sub(1);
+        let extends_tip = self.tip().is_some_and(|tip| tip.number == ancestor)
+            && self.contains_parent(first);
+        let event = match self.tip().is_none() || self.contains(&head) || extends_tip {
+            true => DriverEvent::NewHead(head),
+            false => DriverEvent::Reorg {
+                common_ancestor: ancestor,
+                head,
+            },
+        };
+        if !self.contains(&head) {
+            for block in chain {
+                self.push(block);
+            }
+        }
+        Some(event)
+    }
+}
+
 /// Driver handles the driving of the batch submission pipeline.
 #[derive(Debug, Default, Clone)]
 pub struct Driver {
@@ -33,7 +194,7 @@ pub struct Driver {
     /// The provider
     provider: Option<Provider<Http>>,
     /// A channel to send messages back to the spawner
-    sender: Option<Sender<Pin<Box<BlockId>>>>,
+    sender: Option<Sender<Pin<Box<DriverEvent>>>>,
 }
 
 impl Driver {
@@ -41,7 +202,7 @@ impl Driver {
     pub fn new(
         provider: Provider<Http>,
         poll_interval: Option<Duration>,
-        sender: Option<Sender<Pin<Box<BlockId>>>>,
+        sender: Option<Sender<Pin<Box<DriverEvent>>>>,
     ) -> Self {
         Self {
             provider: Some(provider),
@@ -53,7 +214,7 @@ impl Driver {
     /// Sets the [Driver] [Sender] channel.
     ///
     /// Returns a mutable reference to the [Driver] instance.
-    pub fn with_channel(&mut self, sender: Sender<Pin<Box<BlockId>>>) -> &mut Self {
+    pub fn with_channel(&mut self, sender: Sender<Pin<Box<DriverEvent>>>) -> &mut Self {
         self.sender = Some(sender);
         self
     }
@@ -75,12 +236,16 @@ impl Driver {
     }
 
     /// Executes the driver
+    ///
+    /// Every polled L1 head is checked against a window of recent L1 blocks,
+    /// and sent on as a [DriverEvent].
     pub async fn execute(
         interval: Duration,
-        sender: Arc<Mutex<Sender<Pin<Box<BlockId>>>>>,
+        sender: Arc<Mutex<Sender<Pin<Box<DriverEvent>>>>>,
         provider: Provider<Http>,
     ) -> Result<()> {
         tracing::info!(target: "archon::driver", "Executing driver...");
+        let mut window = L1Window::default();
         let mut first_iter = true;
         loop {
             // Await the poll interval at the loop start so we can ergonomically continue below.
@@ -106,41 +271,93 @@ impl Driver {
             };
             tracing::info!(target: "archon::driver", "Fetched latest l1 block");
 
-            // Derive a [BlockId] from the fetched [Block].
-            let block_id = if let Some(h) = l1_tip.hash {
-                BlockId::from(h)
-            } else if let Some(n) = l1_tip.number {
-                BlockId::from(n)
-            } else {
-                tracing::warn!(target: "archon::driver", "block response missing both number and hash, failed to construct block id!");
-                continue
+            // Derive an [L1Head] from the fetched [Block].
+            let head = match L1Head::from_block(&l1_tip) {
+                Some(head) => head,
+                None => {
+                    tracing::warn!(target: "archon::driver", "block response missing number or hash, failed to construct l1 head!");
+                    continue
+                }
+            };
+            tracing::info!(target: "archon::driver", "Latest L1 block: {} ({:?})", head.number, head.hash);
+
+            // Walk back from the head until it connects to the window.
+            let chain = match Driver::connect_to_window(&provider, &window, head).await {
+                Ok(chain) => chain,
+                Err(e) => {
+                    tracing::warn!(target: "archon::driver", "failed to connect l1 head to known chain!\nError: {}", e);
+                    continue
+                }
             };
-            tracing::info!(target: "archon::driver", "Latest L1 block id: {:?}", block_id);
+            let event = match window.extend(chain) {
+                Some(event) => event,
+                None => continue,
+            };
+            if let DriverEvent::Reorg {
+                common_ancestor, ..
+            } = event
+            {
+                tracing::warn!(target: "archon::driver", "L1 reorg detected, common ancestor: {}", common_ancestor);
+            }
 
-            // Pass back the latest L1 block id to the spawner.
+            // Pass back the event to the spawner.
             // We lock here and not across the loop to prevent deadlocking other threads.
             let locked = if let Ok(s) = sender.lock() {
                 s
             } else {
                 continue
             };
-            if let Err(e) = locked.send(Box::pin(block_id)) {
-                tracing::warn!(target: "archon::driver", "failed to send block id {:?} to spawner: {}", block_id, e);
+            if let Err(e) = locked.send(Box::pin(event)) {
+                tracing::warn!(target: "archon::driver", "failed to send driver event {:?} to spawner: {}", event, e);
+            }
+        }
+    }
+
+    /// Fetches the ancestors of the L1 head until one's parent is in the [L1Window].
+    ///
+    /// Returns the chain from the first block after the common ancestor up to the head.
+    /// If no common ancestor is found within the window, the chain starts right after
+    /// the window's oldest block.
+    pub async fn connect_to_window(
+        provider: &Provider<Http>,
+        window: &L1Window,
+        head: L1Head,
+    ) -> Result<Vec<L1Head>> {
+        let mut chain = vec![head];
+        let oldest = match window.oldest() {
+            Some(oldest) => oldest.number,
+            None => return Ok(chain),
+        };
+        let mut current = head;
+        while !window.contains(&current) && !window.contains_parent(&current) {
+            if current.number <= oldest + 1 {
+                tracing::error!(target: "archon::driver", "L1 reorg deeper than the reorg window");
+                break
             }
+            let parent = provider
+                .get_block(current.parent_hash)
+                .await?
+                .as_ref()
+                .and_then(L1Head::from_block)
+                .ok_or(eyre::eyre!("missing l1 block {:?}", current.parent_hash))?;
+            chain.push(parent);
+            current = parent;
         }
+        chain.reverse();
+        Ok(chain)
     }
 }
 
 impl Stage for Driver {
     type Input = ();
-    type Output = BlockId;
+    type Output = DriverEvent;
 
     /// Builds a new [Driver] instance.
     fn build(
         &mut self,
         pipeline: &mut Archon,
         _receiver: Option<Receiver<Pin<Box<Self::Input>>>>,
-    ) -> Result<Receiver<Pin<Box<BlockId>>>> {
+    ) -> Result<Receiver<Pin<Box<DriverEvent>>>> {
         let (sender, receiver) = channel::<Pin<Box<Self::Output>>>();
         let l1_client = pipeline.config().get_l1_client().unwrap();
         let poll_interval = pipeline.config().polling_interval;
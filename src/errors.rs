@@ -17,6 +17,18 @@ pub enum ConfigError {
     /// L2 Client URL is invalid
     #[error("l2 client url is invalid")]
     InvalidL2ClientUrl,
+    /// The batcher inbox does not match the rollup node's batch inbox
+    #[error("batcher inbox {0:?} does not match the rollup node's batch inbox {1:?}")]
+    BatchInboxMismatch(ethers_core::types::Address, ethers_core::types::Address),
+    /// The L1 chain id does not match the rollup node's L1 chain id
+    #[error("l1 chain id {0} does not match the rollup node's l1 chain id {1}")]
+    L1ChainIdMismatch(u64, u64),
+    /// The L2 chain id does not match the rollup node's L2 chain id
+    #[error("l2 chain id {0} does not match the rollup node's l2 chain id {1}")]
+    L2ChainIdMismatch(u64, u64),
+    /// The proposer's calldata transactions would be pending alongside the batcher's blobs
+    #[error("proposer cannot share batcher account {0:?} while the batcher posts blobs")]
+    SharedBlobAccount(ethers_core::types::Address),
 }
 
 /// [ChannelManager] Error
@@ -40,6 +52,112 @@ pub enum ChannelManagerError {
     /// Channel Manager failed to lock the sender
     #[error("failed to lock the sender")]
     SenderLock,
+    /// There is no pending frame to submit
+    #[error("no tx data available")]
+    NoTxData,
+}
+
+/// [ChannelOut] Error
+#[derive(Debug, Error)]
+pub enum ChannelOutError {
+    /// The maximum frame size cannot fit the frame overhead
+    #[error("max frame size too small")]
+    MaxFrameSizeTooSmall,
+    /// The channel exceeded the maximum number of frames
+    #[error("too many frames in channel")]
+    TooManyFrames,
+    /// Frame bytes could not be decoded
+    #[error("invalid frame")]
+    InvalidFrame,
+    /// The channel is closed
+    #[error("channel out closed")]
+    ChannelClosed,
+    /// The batch would exceed the maximum RLP bytes per channel
+    #[error("too many rlp bytes in channel")]
+    TooManyRlpBytes,
+}
+
+/// Batch Error
+#[derive(Debug, Error)]
+pub enum BatchError {
+    /// The L2 block does not start with an L1 info deposit
+    #[error("missing l1 info deposit")]
+    MissingL1InfoDeposit,
+    /// The L1 info deposit calldata could not be parsed
+    #[error("invalid l1 info deposit")]
+    InvalidL1InfoDeposit,
+    /// The batch could not be decoded
+    #[error("invalid batch")]
+    InvalidBatch,
+    /// The batch type is not supported
+    #[error("unsupported batch type")]
+    UnsupportedBatchType,
+    /// A span batch must contain at least one L2 block
+    #[error("empty span batch")]
+    EmptySpanBatch,
+    /// A batch transaction could not be decoded
+    #[error("invalid batch transaction")]
+    InvalidTransaction,
+    /// The L2 transaction type cannot be encoded into a batch
+    #[error("unsupported transaction type: {0}")]
+    UnsupportedTransactionType(u64),
+}
+
+/// [Blob] Error
+#[derive(Debug, Error)]
+pub enum BlobError {
+    /// The data does not fit into a single blob
+    #[error("blob data too large: {0} bytes")]
+    DataTooLarge(usize),
+    /// The blob encoding version is not supported
+    #[error("unsupported blob encoding version {0}")]
+    UnsupportedEncodingVersion(u8),
+    /// A field element of the blob has its two highest order bits set
+    #[error("invalid field element {0}")]
+    InvalidFieldElement(usize),
+    /// The encoded data length exceeds the maximum blob data size
+    #[error("invalid blob data length {0}")]
+    InvalidLength(usize),
+    /// The blob has non-zero bytes after the encoded data
+    #[error("non-zero blob padding")]
+    NonZeroPadding,
+    /// The KZG commitment or proofs of a blob could not be computed
+    #[error("kzg error: {0}")]
+    Kzg(String),
+}
+
+/// [crate::altda::DaClient] Error
+#[derive(Debug, Error)]
+pub enum AltDaError {
+    /// The request to the alt-DA server failed
+    #[error("alt-da request failed: {0}")]
+    Request(String),
+    /// The alt-DA server answered with an unexpected HTTP status
+    #[error("unexpected alt-da server status {0}")]
+    UnexpectedStatus(u16),
+    /// The alt-DA server answered without a commitment
+    #[error("empty alt-da commitment")]
+    EmptyCommitment,
+}
+
+/// [crate::proposer::Proposer] Error
+#[derive(Debug, Error)]
+pub enum ProposerError {
+    /// Missing rollup node
+    #[error("missing rollup node")]
+    MissingRollupNode,
+    /// Missing provider
+    #[error("missing provider")]
+    MissingProvider,
+    /// Missing transaction manager
+    #[error("missing transaction manager")]
+    MissingTransactionManager,
+    /// The proposer's transaction manager stopped
+    #[error("transaction manager stopped")]
+    TransactionManagerStopped,
+    /// The L2OutputOracle call returned an unexpected result
+    #[error("invalid l2 output oracle call result of {0} bytes")]
+    InvalidCallResult(usize),
 }
 
 /// [TransactionManager] Error
@@ -82,4 +200,37 @@ pub enum TransactionManagerError {
     /// Missing sender private key
     #[error("missing sender private key")]
     MissingSenderPrivateKey,
+    /// The sender private key is not a valid secp256k1 private key
+    #[error("invalid sender private key")]
+    InvalidSenderPrivateKey,
+    /// Only EIP-1559 transactions can be resubmitted
+    #[error("unsupported transaction type")]
+    UnsupportedTransactionType,
+    /// The latest L1 block has no base fee
+    #[error("missing l1 base fee")]
+    MissingBaseFee,
+    /// The sender private key does not belong to the sender address
+    #[error("sender private key does not match the sender address")]
+    SenderAddressMismatch,
+    /// The [crate::nonce::NonceManager] failed to lock the next nonce
+    #[error("failed to lock the nonce")]
+    NonceLock,
+    /// No published transaction was mined within the resubmission timeout
+    #[error("no transaction receipt within the resubmission timeout")]
+    ReceiptTimeout,
+    /// The transaction was mined, but reverted
+    #[error("transaction reverted")]
+    TransactionReverted,
+    /// The transaction was mined, but not sent to the batch inbox
+    #[error("transaction sent to unexpected address {0:?}")]
+    UnexpectedRecipient(Option<ethers_core::types::Address>),
+    /// The transaction was replaced by a mined cancellation
+    #[error("transaction cancelled")]
+    TransactionCancelled,
+    /// The data availability type is not supported
+    #[error("unsupported data availability type")]
+    UnsupportedDataAvailabilityType,
+    /// Alt-DA transactions require the url of an alt-DA server
+    #[error("missing alt-da server")]
+    MissingDaServer,
 }
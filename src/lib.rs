@@ -2,7 +2,6 @@
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 #![forbid(unsafe_code)]
-#![forbid(where_clauses_object_safety)]
 
 /// Archon Telemetry
 pub mod telemetry;
@@ -31,9 +30,27 @@ pub mod channels;
 /// The channel builder
 pub mod builder;
 
+/// Batch encoding
+pub mod batch;
+
+/// Span batch encoding
+pub mod span_batch;
+
 /// The transaction manager
 pub mod transactions;
 
+/// Transaction nonce management
+pub mod nonce;
+
+/// EIP-4844 blob encoding and transactions
+pub mod blobs;
+
+/// Alt-DA server client
+pub mod altda;
+
+/// The L2 output proposer
+pub mod proposer;
+
 /// The metrics server
 pub mod metrics;
 
@@ -43,6 +60,9 @@ pub(crate) mod macros;
 /// Pipeline Builder
 pub mod pipeline_builder;
 
+/// Mock L2 blocks for tests and benches
+pub mod test_utils;
+
 /// Re-export Archon Types
 pub mod prelude {
     pub use crate::{
@@ -63,10 +83,22 @@ pub mod prelude {
     /// Re-export rollup-related types.
     pub use crate::rollup::*;
 
+    /// Re-export alt-DA types.
+    pub use crate::altda::*;
+    /// Re-export blob types.
+    pub use crate::blobs::*;
+    /// Re-export nonce management types.
+    pub use crate::nonce::*;
+    /// Re-export proposer types.
+    pub use crate::proposer::*;
     /// Re-export transaction manager related types.
     pub use crate::transactions::*;
 
+    /// Re-export batch-related types.
+    pub use crate::batch::*;
     pub use crate::builder::*;
     /// Re-export channel-related types.
     pub use crate::channels::*;
+    /// Re-export span batch types.
+    pub use crate::span_batch::*;
 }
@@ -5,8 +5,15 @@ use std::{
         TcpListener,
         TcpStream,
     },
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
 };
 
+/// Counts the channel pipeline resets caused by L2 reorgs.
+pub static L2_REORG_RESETS: AtomicU64 = AtomicU64::new(0);
+
 /// Metrics
 ///
 /// Serves metrics for the [crate::client::Archon] client.
@@ -39,6 +46,21 @@ impl Metrics {
             read_bytes,
             String::from_utf8_lossy(&buffer[..])
         );
+        let body = self.render();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes())?;
         Ok(())
     }
+
+    /// Renders the [crate::client::Archon] metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        format!(
+            "# TYPE archon_l2_reorg_resets counter\narchon_l2_reorg_resets {}\n",
+            L2_REORG_RESETS.load(Ordering::Relaxed)
+        )
+    }
 }
@@ -0,0 +1,161 @@
+use std::{
+    collections::BTreeSet,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use ethers_core::types::{
+    Address,
+    BlockNumber,
+};
+use ethers_providers::{
+    Http,
+    Middleware,
+    Provider,
+};
+use eyre::Result;
+
+use crate::errors::TransactionManagerError;
+
+/// Nonce Manager
+///
+/// Hands out sequential nonces to the transactions published by the
+/// [crate::transactions::TransactionManager], so that several transactions can be
+/// pending at once without colliding on a nonce.
+///
+/// The [NonceManager] is synced lazily from the pending transaction count of the sender
+/// on L1. After a [NonceManager::reset], e.g. because L1 reorged, the next nonce is synced
+/// from L1 again.
+///
+/// Every nonce handed out is in flight until its transaction is settled or released.
+/// A nonce released by a transaction that failed to send is handed out again before
+/// any new nonce, since later nonces in flight cannot be mined until it is used.
+///
+/// Clones of a [NonceManager] share the same nonces.
+#[derive(Debug, Clone, Default)]
+pub struct NonceManager {
+    /// The nonces handed out
+    nonces: Arc<Mutex<Nonces>>,
+}
+
+/// The nonces handed out by a [NonceManager].
+#[derive(Debug, Default)]
+struct Nonces {
+    /// The next new nonce to hand out, or `None` if it must be synced from L1
+    next: Option<u64>,
+    /// The nonces handed out whose transactions are in flight
+    in_flight: BTreeSet<u64>,
+    /// The nonces released by failed transactions, to be handed out again
+    released: BTreeSet<u64>,
+}
+
+impl NonceManager {
+    /// Constructs a new, unsynced [NonceManager]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the next nonce for the sender, syncing it from L1 first if necessary.
+    pub async fn next_nonce(
+        &self,
+        provider: &Provider<Http>,
+        sender: Address,
+    ) -> Result<u64> {
+        loop {
+            if let Some(nonce) = self.reserve()? {
+                return Ok(nonce)
+            }
+            let pending = provider
+                .get_transaction_count(sender, Some(BlockNumber::Pending.into()))
+                .await?;
+            tracing::debug!(target: "archon::nonce", "Synced nonce {} from L1", pending);
+            self.sync(pending.as_u64())?;
+        }
+    }
+
+    /// Hands out the lowest released nonce, or else the next nonce.
+    ///
+    /// Returns `None` if the [NonceManager] is not synced.
+    pub fn reserve(&self) -> Result<Option<u64>> {
+        let mut nonces = self.lock()?;
+        let nonce = match nonces.released.pop_first() {
+            Some(nonce) => nonce,
+            None => match nonces.next {
+                Some(next) => {
+                    nonces.next = Some(next + 1);
+                    next
+                }
+                None => return Ok(None),
+            },
+        };
+        nonces.in_flight.insert(nonce);
+        Ok(Some(nonce))
+    }
+
+    /// Marks the nonce of a mined transaction as used.
+    pub fn settle(&self, nonce: u64) -> Result<()> {
+        self.lock()?.in_flight.remove(&nonce);
+        Ok(())
+    }
+
+    /// Hands back the nonce of a transaction that failed to send.
+    ///
+    /// While other nonces are in flight, the nonce is handed out again before any new nonce.
+    /// Otherwise, the next nonce is resynced from L1, which has no later transactions pending.
+    pub fn release(&self, nonce: u64) -> Result<()> {
+        let mut nonces = self.lock()?;
+        nonces.in_flight.remove(&nonce);
+        if nonces.in_flight.is_empty() {
+            tracing::debug!(target: "archon::nonce", "Released nonce {} with no other nonce in flight, resyncing", nonce);
+            nonces.next = None;
+            nonces.released.clear();
+        } else if nonces.next.is_some_and(|next| nonce < next) {
+            tracing::debug!(target: "archon::nonce", "Released nonce {}, handing it out next", nonce);
+            nonces.released.insert(nonce);
+        }
+        Ok(())
+    }
+
+    /// Syncs the next nonce to the given L1 pending transaction count.
+    ///
+    /// This is a no-op if the [NonceManager] is already synced, so that concurrent
+    /// syncs do not hand out the same nonce twice.
+    pub fn sync(&self, pending_count: u64) -> Result<()> {
+        self.lock()?.next.get_or_insert(pending_count);
+        Ok(())
+    }
+
+    /// Forgets the next nonce and the released nonces,
+    /// so that the next nonce is synced from L1 before it is handed out again.
+    pub fn reset(&self) -> Result<()> {
+        let mut nonces = self.lock()?;
+        nonces.next = None;
+        nonces.released.clear();
+        Ok(())
+    }
+
+    /// Returns whether the next nonce is known.
+    pub fn is_synced(&self) -> bool {
+        self.nonces
+            .lock()
+            .map(|nonces| nonces.next.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Returns the number of nonces handed out whose transactions are in flight.
+    pub fn in_flight(&self) -> usize {
+        self.nonces
+            .lock()
+            .map(|nonces| nonces.in_flight.len())
+            .unwrap_or_default()
+    }
+
+    /// Locks the nonces.
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Nonces>> {
+        self.nonces
+            .lock()
+            .map_err(|_| TransactionManagerError::NonceLock.into())
+    }
+}
@@ -21,7 +21,7 @@ pub struct PipelineBuilder<'a, T: Stage = ()> {
 
 impl<'a, T: Stage> PipelineBuilder<'a, T> {
     /// Constructs a new PipelineBuilder with an [Archon] Instance.
-    pub fn new(pipeline: &mut Archon) -> PipelineBuilder<()> {
+    pub fn new(pipeline: &mut Archon) -> PipelineBuilder<'_, ()> {
         PipelineBuilder {
             pipeline,
             receiver: Default::default(),
@@ -34,11 +34,14 @@ impl<'a, T: Stage> PipelineBuilder<'a, T> {
     }
 
     /// Builds an actor stage returning the receiver
-    pub fn channel<S: Stage>(self, mut stage: S) -> PipelineBuilder<'a, S> {
-        let (_, receiver) = channel::<Pin<Box<S::Input>>>();
-
+    ///
+    /// The receiver of the previous stage's output is passed in as the stage's input.
+    pub fn channel<S: Stage<Input = T::Output>>(
+        self,
+        mut stage: S,
+    ) -> PipelineBuilder<'a, S> {
         // Remove unwrap? breaks the .channel() chain to
-        let receiver = stage.build(self.pipeline, Some(receiver)).unwrap();
+        let receiver = stage.build(self.pipeline, self.receiver).unwrap();
 
         PipelineBuilder {
             pipeline: self.pipeline,
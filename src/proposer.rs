@@ -0,0 +1,475 @@
+//! Proposer
+//!
+//! Proposes L2 output roots on L1, either by creating dispute games through the
+//! DisputeGameFactory or by proposing them to the L2OutputOracle.
+
+use std::{
+    pin::Pin,
+    sync::mpsc::{
+        self,
+        Receiver,
+        Sender,
+        TryRecvError,
+    },
+    time::Duration,
+};
+
+use bytes::Bytes;
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction,
+    Address,
+    TransactionRequest,
+    H256,
+    U256,
+};
+use ethers_providers::{
+    Http,
+    Middleware,
+    Provider,
+};
+use eyre::Result;
+use tokio::task::JoinHandle;
+
+use crate::{
+    channels::{
+        TaggedData,
+        TransactionID,
+    },
+    errors::ProposerError,
+    rollup::{
+        OutputResponse,
+        RollupNode,
+        SyncStatus,
+    },
+    transactions::{
+        TransactionManager,
+        TransactionOutcome,
+    },
+};
+
+/// The selector of `proposeL2Output(bytes32,uint256,bytes32,uint256)`.
+pub const PROPOSE_L2_OUTPUT_SELECTOR: [u8; 4] = [0x9a, 0xaa, 0xb6, 0x48];
+
+/// The selector of `nextBlockNumber()`.
+pub const NEXT_BLOCK_NUMBER_SELECTOR: [u8; 4] = [0xdc, 0xec, 0x33, 0x48];
+
+/// The selector of `create(uint32,bytes32,bytes)` on the DisputeGameFactory.
+pub const CREATE_GAME_SELECTOR: [u8; 4] = [0x82, 0xec, 0xf2, 0xf6];
+
+/// The selector of `games(uint32,bytes32,bytes)` on the DisputeGameFactory.
+pub const GAMES_SELECTOR: [u8; 4] = [0x5f, 0x01, 0x50, 0xcb];
+
+/// The default interval between dispute game claims, in L2 blocks.
+pub const DEFAULT_SUBMISSION_INTERVAL: u64 = 1800;
+
+/// The default bond posted with every dispute game, in wei.
+pub const DEFAULT_GAME_BOND: u64 = 80_000_000_000_000_000;
+
+/// The default interval at which the [Proposer] polls for new outputs to propose.
+pub const DEFAULT_PROPOSAL_INTERVAL: Duration = Duration::from_secs(12);
+
+/// Where the [Proposer] proposes L2 outputs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalTarget {
+    /// Outputs are proposed to the L2OutputOracle at the given address.
+    L2OutputOracle(Address),
+    /// Outputs are claimed by creating dispute games through a DisputeGameFactory.
+    DisputeGameFactory(DisputeGameConfig),
+}
+
+impl ProposalTarget {
+    /// Returns the address of the contract proposals are sent to.
+    pub fn address(&self) -> Address {
+        match self {
+            Self::L2OutputOracle(address) => *address,
+            Self::DisputeGameFactory(game) => game.factory,
+        }
+    }
+}
+
+/// Configures the dispute games created by the [Proposer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisputeGameConfig {
+    /// The address of the DisputeGameFactory
+    pub factory: Address,
+    /// The type of the dispute games
+    pub game_type: u32,
+    /// The bond posted with every dispute game, in wei
+    pub bond: U256,
+    /// The interval between claimed L2 blocks
+    pub submission_interval: u64,
+}
+
+impl DisputeGameConfig {
+    /// Returns the latest L2 block at or below the L2 head to claim an output at.
+    ///
+    /// Claims are made at multiples of the submission interval, so that repeated polls
+    /// claim the same blocks.
+    pub fn claim_block(&self, l2_head: u64) -> Option<u64> {
+        let block = l2_head - l2_head % self.submission_interval.max(1);
+        (block > 0).then_some(block)
+    }
+}
+
+/// Configures which L2 outputs the [Proposer] proposes, and where to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProposerConfig {
+    /// Where outputs are proposed to
+    pub target: ProposalTarget,
+    /// The interval at which new outputs to propose are polled for
+    pub poll_interval: Duration,
+    /// Whether outputs up to the safe L2 head are proposed, instead of up to the finalized L2 head
+    pub allow_non_finalized: bool,
+}
+
+impl Default for ProposerConfig {
+    fn default() -> Self {
+        Self {
+            target: ProposalTarget::L2OutputOracle(Address::zero()),
+            poll_interval: DEFAULT_PROPOSAL_INTERVAL,
+            allow_non_finalized: false,
+        }
+    }
+}
+
+impl ProposerConfig {
+    /// Returns the L2 head up to which outputs may be proposed.
+    pub fn l2_head(&self, sync_status: &SyncStatus) -> u64 {
+        match self.allow_non_finalized {
+            true => sync_status.safe_l2.number,
+            false => sync_status.finalized_l2.number,
+        }
+    }
+}
+
+/// A proposal of an L2 output root to the L2OutputOracle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputProposal {
+    /// The output root
+    pub output_root: H256,
+    /// The number of the L2 block the output root commits to
+    pub l2_block_number: u64,
+    /// The hash of the L1 block the proposal is anchored to
+    pub l1_block_hash: H256,
+    /// The number of the L1 block the proposal is anchored to
+    pub l1_block_number: u64,
+}
+
+impl OutputProposal {
+    /// Constructs an [OutputProposal] of the output, anchored to the L1 block the rollup node
+    /// was deriving from when it computed the output.
+    ///
+    /// The L2OutputOracle rejects the proposal if the anchor is no longer canonical,
+    /// so that proposals do not outlive an L1 reorg.
+    pub fn new(output: &OutputResponse) -> Self {
+        Self {
+            output_root: output.output_root,
+            l2_block_number: output.block_ref.number,
+            l1_block_hash: output.sync_status.current_l1.hash,
+            l1_block_number: output.sync_status.current_l1.number,
+        }
+    }
+
+    /// Returns the ABI-encoded `proposeL2Output` call of the proposal.
+    pub fn calldata(&self) -> Bytes {
+        let mut out = Vec::with_capacity(4 + 4 * 32);
+        out.extend_from_slice(&PROPOSE_L2_OUTPUT_SELECTOR);
+        out.extend_from_slice(self.output_root.as_bytes());
+        out.extend_from_slice(&abi_word(self.l2_block_number));
+        out.extend_from_slice(self.l1_block_hash.as_bytes());
+        out.extend_from_slice(&abi_word(self.l1_block_number));
+        Bytes::from(out)
+    }
+}
+
+/// A claim of an L2 output root, made by creating a dispute game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameClaim {
+    /// The type of the dispute game
+    pub game_type: u32,
+    /// The claimed output root
+    pub root_claim: H256,
+    /// The number of the L2 block the output root commits to
+    pub l2_block_number: u64,
+}
+
+impl GameClaim {
+    /// Constructs a [GameClaim] of the output in a dispute game of the given type.
+    pub fn new(game_type: u32, output: &OutputResponse) -> Self {
+        Self {
+            game_type,
+            root_claim: output.output_root,
+            l2_block_number: output.block_ref.number,
+        }
+    }
+
+    /// Returns the extra data of the dispute game, the ABI-encoded L2 block number.
+    pub fn extra_data(&self) -> [u8; 32] {
+        abi_word(self.l2_block_number)
+    }
+
+    /// Returns the ABI-encoded `create` call of the claim.
+    pub fn create_calldata(&self) -> Bytes {
+        self.encode_call(CREATE_GAME_SELECTOR)
+    }
+
+    /// Returns the ABI-encoded `games` call, looking up the dispute game of the claim.
+    pub fn games_calldata(&self) -> Bytes {
+        self.encode_call(GAMES_SELECTOR)
+    }
+
+    /// Encodes a call of the `(uint32 gameType, bytes32 rootClaim, bytes extraData)` arguments.
+    fn encode_call(&self, selector: [u8; 4]) -> Bytes {
+        let mut out = Vec::with_capacity(4 + 5 * 32);
+        out.extend_from_slice(&selector);
+        out.extend_from_slice(&abi_word(self.game_type as u64));
+        out.extend_from_slice(self.root_claim.as_bytes());
+        // The offset and length of the dynamic extra data
+        out.extend_from_slice(&abi_word(3 * 32));
+        out.extend_from_slice(&abi_word(32));
+        out.extend_from_slice(&self.extra_data());
+        Bytes::from(out)
+    }
+}
+
+/// Encodes the integer as a 32-byte ABI word.
+fn abi_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    U256::from(value).to_big_endian(&mut word);
+    word
+}
+
+/// Proposer
+///
+/// Polls the rollup node for the finalized, or safe, L2 head. Whenever the head reaches the
+/// next block number expected by the L2OutputOracle, the output at that block is proposed
+/// through the [Proposer]'s own [TransactionManager].
+///
+/// With a DisputeGameFactory instead, the output at every submission interval is claimed by
+/// creating a dispute game, unless a game for the same claim already exists.
+///
+/// Only one proposal is in flight at a time. A failed proposal is retried on the next poll.
+#[derive(Debug, Default)]
+pub struct Proposer {
+    /// The [ProposerConfig]
+    config: ProposerConfig,
+    /// The rollup node outputs are fetched from
+    rollup_node: Option<RollupNode>,
+    /// The L1 provider
+    provider: Option<Provider<Http>>,
+    /// The [TransactionManager] that submits proposals
+    tx_manager: Option<TransactionManager>,
+}
+
+impl Proposer {
+    /// Constructs a new [Proposer]
+    pub fn new(
+        config: ProposerConfig,
+        rollup_node: RollupNode,
+        provider: Provider<Http>,
+    ) -> Self {
+        Self {
+            config,
+            rollup_node: Some(rollup_node),
+            provider: Some(provider),
+            tx_manager: None,
+        }
+    }
+
+    /// Sets the [TransactionManager] that submits proposals.
+    ///
+    /// The [TransactionManager] must send its transactions to the [ProposalTarget]: the
+    /// DisputeGameFactory if one is configured, otherwise the L2OutputOracle.
+    pub fn with_transaction_manager(
+        &mut self,
+        tx_manager: TransactionManager,
+    ) -> &mut Self {
+        self.tx_manager = Some(tx_manager);
+        self
+    }
+
+    /// Returns the [TransactionManager] that submits proposals, if set.
+    pub fn transaction_manager(&self) -> Option<&TransactionManager> {
+        self.tx_manager.as_ref()
+    }
+
+    /// Spawns the [Proposer] and its [TransactionManager]
+    pub fn spawn(self) -> Result<JoinHandle<Result<()>>> {
+        let rollup_node = self.rollup_node.ok_or(ProposerError::MissingRollupNode)?;
+        let provider = self.provider.ok_or(ProposerError::MissingProvider)?;
+        let mut tx_manager = self
+            .tx_manager
+            .ok_or(ProposerError::MissingTransactionManager)?;
+        let (sender, tx_manager_receiver) = mpsc::channel::<Pin<Box<TaggedData>>>();
+        let (tx_manager_sender, outcome_receiver) =
+            mpsc::channel::<Pin<Box<TransactionOutcome>>>();
+        tx_manager.with_sender(tx_manager_sender);
+        tx_manager.with_receiver(tx_manager_receiver);
+        let tx_manager_handle = tx_manager.spawn()?;
+        let config = self.config;
+        let proposer_handle = tokio::spawn(async move {
+            tracing::info!(target: "archon::proposer", "Spawning proposer in new thread...");
+            Proposer::execute(
+                config,
+                rollup_node,
+                provider,
+                sender,
+                outcome_receiver,
+                tx_manager_handle,
+            )
+            .await
+        });
+        Ok(proposer_handle)
+    }
+
+    /// Executes the proposer
+    pub async fn execute(
+        config: ProposerConfig,
+        rollup_node: RollupNode,
+        provider: Provider<Http>,
+        sender: Sender<Pin<Box<TaggedData>>>,
+        outcome_receiver: Receiver<Pin<Box<TransactionOutcome>>>,
+        tx_manager_handle: JoinHandle<Result<()>>,
+    ) -> Result<()> {
+        tracing::info!(target: "archon::proposer", "Executing proposer...");
+        let mut pending: Option<u64> = None;
+        let mut first_iter = true;
+        loop {
+            // Await the poll interval at the loop start so we can ergonomically continue below.
+            if !first_iter {
+                tokio::time::sleep(config.poll_interval).await;
+            }
+            first_iter = false;
+            if tx_manager_handle.is_finished() {
+                return Err(ProposerError::TransactionManagerStopped.into())
+            }
+
+            // Settle the proposal in flight
+            loop {
+                match outcome_receiver.try_recv() {
+                    Ok(outcome) => match &*outcome {
+                        TransactionOutcome::Confirmed(id, receipt) => {
+                            tracing::info!(target: "archon::proposer", "Proposed output {} in transaction {:?}", id, receipt.transaction_hash);
+                            pending = None;
+                        }
+                        TransactionOutcome::Failed(id) => {
+                            tracing::warn!(target: "archon::proposer", "Failed to propose output {}, retrying", id);
+                            pending = None;
+                        }
+                    },
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        return Err(ProposerError::TransactionManagerStopped.into())
+                    }
+                }
+            }
+            if let Some(block_number) = pending {
+                tracing::debug!(target: "archon::proposer", "Output proposal at block {} is still in flight", block_number);
+                continue
+            }
+
+            // Build the next proposal, once the L2 head has reached it
+            let (l2_block_number, calldata) = match Proposer::next_proposal(
+                &config,
+                &rollup_node,
+                &provider,
+            )
+            .await
+            {
+                Ok(Some(proposal)) => proposal,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::error!(target: "archon::proposer", "Failed to build the next proposal: {:?}", e);
+                    continue
+                }
+            };
+            let tagged =
+                TaggedData::new(calldata, TransactionID::output(l2_block_number));
+            sender
+                .send(Box::pin(tagged))
+                .map_err(|_| ProposerError::TransactionManagerStopped)?;
+            pending = Some(l2_block_number);
+        }
+    }
+
+    /// Returns the L2 block number and calldata of the next proposal,
+    /// or `None` if there is nothing to propose yet.
+    pub async fn next_proposal(
+        config: &ProposerConfig,
+        rollup_node: &RollupNode,
+        provider: &Provider<Http>,
+    ) -> Result<Option<(u64, Bytes)>> {
+        let sync_status = rollup_node.sync_status().await?;
+        let l2_head = config.l2_head(&sync_status);
+        match config.target {
+            ProposalTarget::L2OutputOracle(l2_output_oracle) => {
+                let next_block_number =
+                    Proposer::next_block_number(provider, l2_output_oracle).await?;
+                if l2_head < next_block_number {
+                    tracing::debug!(target: "archon::proposer", "L2 head {} has not reached the next output at block {}", l2_head, next_block_number);
+                    return Ok(None)
+                }
+                let proposal = Proposer::proposal(rollup_node, next_block_number).await?;
+                tracing::info!(target: "archon::proposer", "Proposing output root {:?} at block {}", proposal.output_root, proposal.l2_block_number);
+                Ok(Some((proposal.l2_block_number, proposal.calldata())))
+            }
+            ProposalTarget::DisputeGameFactory(game) => {
+                let Some(l2_block_number) = game.claim_block(l2_head) else {
+                    tracing::debug!(target: "archon::proposer", "L2 head {} has not reached the first claim", l2_head);
+                    return Ok(None)
+                };
+                let output = rollup_node.output_at_block(l2_block_number).await?;
+                let claim = GameClaim::new(game.game_type, &output);
+                if Proposer::game_exists(provider, game.factory, &claim).await? {
+                    tracing::debug!(target: "archon::proposer", "Dispute game for output root {:?} at block {} already exists", claim.root_claim, l2_block_number);
+                    return Ok(None)
+                }
+                tracing::info!(target: "archon::proposer", "Creating dispute game of type {} for output root {:?} at block {}", claim.game_type, claim.root_claim, l2_block_number);
+                Ok(Some((l2_block_number, claim.create_calldata())))
+            }
+        }
+    }
+
+    /// Builds the [OutputProposal] of the output at the given L2 block.
+    pub async fn proposal(
+        rollup_node: &RollupNode,
+        l2_block_number: u64,
+    ) -> Result<OutputProposal> {
+        let output = rollup_node.output_at_block(l2_block_number).await?;
+        Ok(OutputProposal::new(&output))
+    }
+
+    /// Returns whether the DisputeGameFactory already created a dispute game for the claim.
+    pub async fn game_exists(
+        provider: &Provider<Http>,
+        factory: Address,
+        claim: &GameClaim,
+    ) -> Result<bool> {
+        let call: TypedTransaction = TransactionRequest::new()
+            .to(factory)
+            .data(claim.games_calldata())
+            .into();
+        let result = provider.call(&call, None).await?;
+        if result.len() < 32 {
+            return Err(ProposerError::InvalidCallResult(result.len()).into())
+        }
+        Ok(!Address::from_slice(&result[12..32]).is_zero())
+    }
+
+    /// Fetches the number of the next L2 block the L2OutputOracle expects an output for.
+    pub async fn next_block_number(
+        provider: &Provider<Http>,
+        l2_output_oracle: Address,
+    ) -> Result<u64> {
+        let call: TypedTransaction = TransactionRequest::new()
+            .to(l2_output_oracle)
+            .data(NEXT_BLOCK_NUMBER_SELECTOR.to_vec())
+            .into();
+        let result = provider.call(&call, None).await?;
+        if result.len() != 32 {
+            return Err(ProposerError::InvalidCallResult(result.len()).into())
+        }
+        Ok(u64::try_from(U256::from_big_endian(&result))
+            .map_err(|_| ProposerError::InvalidCallResult(result.len()))?)
+    }
+}
@@ -3,8 +3,9 @@
 //! Encapsulates logic for interacting with a rollup node.
 
 use ethers_core::types::{
-    BlockId,
+    Address,
     H256,
+    U64,
 };
 use ethers_providers::{
     Http,
@@ -16,6 +17,9 @@ use serde::{
     Serialize,
 };
 
+/// The channel timeout since the Granite hardfork, in L1 blocks.
+pub const CHANNEL_TIMEOUT_GRANITE: u64 = 50;
+
 /// A Rollup Node
 #[derive(Debug, Clone, Default)]
 pub struct RollupNode {
@@ -33,12 +37,14 @@ impl RollupNode {
     }
 
     /// Fetches the output of the rollup node as a [`OutputResponse`].
+    ///
+    /// The op-node expects the block number as a hex quantity.
     pub async fn output_at_block(&self, block_num: u64) -> Result<OutputResponse> {
         let output = self
             .client
             .as_ref()
             .unwrap()
-            .request("optimism_outputAtBlock", vec![block_num])
+            .request("optimism_outputAtBlock", vec![U64::from(block_num)])
             .await?;
         Ok(output)
     }
@@ -58,8 +64,8 @@ impl RollupNode {
         Ok(sync_status)
     }
 
-    /// Fetches the rollup-node's config as a [`serde_json::Value`].
-    pub async fn rollup_config(&self) -> Result<serde_json::Value> {
+    /// Fetches the rollup-node's config as a [`RollupConfig`].
+    pub async fn rollup_config(&self) -> Result<RollupConfig> {
         let empty_params: Vec<String> = Vec::new();
         let config = self
             .client
@@ -83,46 +89,152 @@ impl RollupNode {
     }
 }
 
+/// The rollup node's RollupConfig.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RollupConfig {
+    /// The genesis state of the rollup
+    pub genesis: Genesis,
+    /// The L2 block time, in seconds
+    pub block_time: u64,
+    /// The maximum number of seconds an L2 block's timestamp may be ahead of its L1 origin
+    pub max_sequencer_drift: u64,
+    /// The number of L1 blocks in which a batch must be included after its L1 origin
+    pub seq_window_size: u64,
+    /// The number of L1 blocks in which all frames of a channel must be included,
+    /// until Granite fixes it to [CHANNEL_TIMEOUT_GRANITE]
+    pub channel_timeout: u64,
+    /// The L1 chain id
+    pub l1_chain_id: u64,
+    /// The L2 chain id
+    pub l2_chain_id: u64,
+    /// The Regolith hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regolith_time: Option<u64>,
+    /// The Canyon hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canyon_time: Option<u64>,
+    /// The Delta hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta_time: Option<u64>,
+    /// The Ecotone hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecotone_time: Option<u64>,
+    /// The Fjord hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fjord_time: Option<u64>,
+    /// The Granite hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub granite_time: Option<u64>,
+    /// The Holocene hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holocene_time: Option<u64>,
+    /// The Isthmus hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isthmus_time: Option<u64>,
+    /// The Jovian hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jovian_time: Option<u64>,
+    /// The Interop hardfork activation time, if scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interop_time: Option<u64>,
+    /// The batch inbox address
+    pub batch_inbox_address: Address,
+    /// The L1 deposit contract address
+    pub deposit_contract_address: Address,
+    /// The L1 system config contract address
+    pub l1_system_config_address: Address,
+}
+
+impl RollupConfig {
+    /// Returns whether the Delta hardfork, which introduces span batches, is active at the
+    /// given L2 timestamp.
+    pub fn is_delta(&self, timestamp: u64) -> bool {
+        self.delta_time.is_some_and(|time| timestamp >= time)
+    }
+
+    /// Returns whether the Ecotone hardfork, which introduces blob transactions, is active at
+    /// the given L2 timestamp.
+    pub fn is_ecotone(&self, timestamp: u64) -> bool {
+        self.ecotone_time.is_some_and(|time| timestamp >= time)
+    }
+
+    /// Returns whether the Granite hardfork, which shortens the channel timeout, is active at
+    /// the given L2 timestamp.
+    pub fn is_granite(&self, timestamp: u64) -> bool {
+        self.granite_time.is_some_and(|time| timestamp >= time)
+    }
+
+    /// Returns the channel timeout at the given L2 timestamp, in L1 blocks.
+    pub fn channel_timeout_at(&self, timestamp: u64) -> u64 {
+        match self.is_granite(timestamp) {
+            true => CHANNEL_TIMEOUT_GRANITE,
+            false => self.channel_timeout,
+        }
+    }
+}
+
+/// The genesis state of a rollup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Genesis {
+    /// The L1 block the rollup starts deriving from
+    pub l1: BlockNumHash,
+    /// The L2 genesis block
+    pub l2: BlockNumHash,
+    /// The L2 genesis block timestamp
+    pub l2_time: u64,
+    /// The initial system config
+    pub system_config: SystemConfig,
+}
+
+/// The rollup's system config at genesis.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemConfig {
+    /// The batcher address
+    pub batcher_addr: Address,
+    /// The L1 fee overhead
+    pub overhead: H256,
+    /// The L1 fee scalar
+    pub scalar: H256,
+    /// The L2 block gas limit
+    pub gas_limit: u64,
+}
+
 /// The current sync status of a rollup node.
-#[derive(
-    Debug,
-    Clone,
-    Serialize,
-    Deserialize,
-    Default,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
 pub struct SyncStatus {
-    /// The current L1 block number.
-    pub current_l1: u64,
-    /// The current L1 finalized block number.
-    pub current_l1_finalized: u64,
-    /// The current L1 head block number.
-    pub head_l1: u64,
-    /// The current L1 safe block number.
-    pub safe_l1: u64,
-    /// The current L1 finalized block number.
-    pub finalized_l1: u64,
-    /// The current L2 head block number.
-    pub unsafe_l2: u64,
-    /// The current L2 safe block number.
-    pub safe_l2: u64,
-    /// The current L2 finalized block number.
-    pub finalized_l2: u64,
+    /// The L1 block the rollup node is currently deriving from.
+    pub current_l1: L1BlockRef,
+    /// The finalized L1 block at the time the current L1 block was derived from.
+    pub current_l1_finalized: L1BlockRef,
+    /// The L1 head block.
+    pub head_l1: L1BlockRef,
+    /// The L1 safe block.
+    pub safe_l1: L1BlockRef,
+    /// The L1 finalized block.
+    pub finalized_l1: L1BlockRef,
+    /// The L2 unsafe head block.
+    pub unsafe_l2: L2BlockRef,
+    /// The L2 safe head block.
+    pub safe_l2: L2BlockRef,
+    /// The L2 finalized head block.
+    pub finalized_l2: L2BlockRef,
+    /// The L2 block the rollup node is deriving towards the safe head.
+    pub pending_safe_l2: L2BlockRef,
+    /// The L2 cross-unsafe head block, which is the unsafe head before interop.
+    pub cross_unsafe_l2: L2BlockRef,
+    /// The L2 local-safe head block, which is the safe head before interop.
+    pub local_safe_l2: L2BlockRef,
 }
 
 /// The rollup node's OutputResponse.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
 pub struct OutputResponse {
     /// The output response version
-    pub version: Vec<u8>,
+    pub version: H256,
     /// The output response output root
-    pub output_root: Vec<u8>,
+    pub output_root: H256,
     /// The output response block ref
     pub block_ref: L2BlockRef,
     /// The output response withdrawal storage root
@@ -133,8 +245,32 @@ pub struct OutputResponse {
     pub sync_status: SyncStatus,
 }
 
+/// A block, identified by its hash and number.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
+pub struct BlockNumHash {
+    /// The block hash
+    pub hash: H256,
+    /// The block number
+    pub number: u64,
+}
+
+/// The rollup node's L1BlockRef.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BlockRef {
+    /// The L1 block ref hash
+    pub hash: H256,
+    /// The L1 block ref number
+    pub number: u64,
+    /// The L1 block ref parent hash
+    pub parent_hash: H256,
+    /// The L1 block ref timestamp
+    pub timestamp: u64,
+}
+
 /// The rollup node's L2BlockRef.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
 pub struct L2BlockRef {
     /// The L2 block ref hash
     pub hash: H256,
@@ -142,11 +278,11 @@ pub struct L2BlockRef {
     pub number: u64,
     /// The L2 block ref parent hash
     pub parent_hash: H256,
-    /// The L2 block ref time
-    pub time: u64,
+    /// The L2 block ref timestamp
+    pub timestamp: u64,
     /// The L2 block ref L1 origin
     #[serde(rename = "l1origin")]
-    pub l1_origin: BlockId,
+    pub l1_origin: BlockNumHash,
     /// The L2 block ref sequence number
     pub sequence_number: u64,
 }
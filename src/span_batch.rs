@@ -0,0 +1,433 @@
+//! Span Batch
+//!
+//! Encapsulates the span batch format, which encodes a range of L2 blocks
+//! into a single batch with columnar transaction fields.
+
+use std::{
+    fmt::{
+        self,
+        Display,
+    },
+    ops::Range,
+    str::FromStr,
+};
+
+use ethers_core::{
+    types::{
+        Address,
+        U256,
+    },
+    utils::rlp::{
+        self,
+        DecoderError,
+        Rlp,
+        RlpStream,
+    },
+};
+use eyre::Result;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    batch::SingularBatch,
+    errors::BatchError,
+};
+
+/// The batch type byte of a [SpanBatch].
+pub const SPAN_BATCH_TYPE: u8 = 0x01;
+
+/// The type of batches written into channels.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum BatchType {
+    /// One [SingularBatch] per L2 block.
+    #[default]
+    Singular,
+    /// One [SpanBatch] per channel.
+    Span,
+}
+
+impl FromStr for BatchType {
+    type Err = BatchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "0" | "singular" => Ok(Self::Singular),
+            "1" | "span" => Ok(Self::Span),
+            _ => Err(BatchError::UnsupportedBatchType),
+        }
+    }
+}
+
+impl Display for BatchType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Singular => write!(f, "singular"),
+            Self::Span => write!(f, "span"),
+        }
+    }
+}
+
+/// A span batch encodes a contiguous range of L2 blocks.
+///
+/// It is encoded as `SPAN_BATCH_TYPE ++ prefix ++ payload` where:
+/// - `prefix = rel_timestamp ++ l1_origin_num ++ parent_check ++ l1_origin_check`
+/// - `payload = block_count ++ origin_bits ++ block_tx_counts ++ txs`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpanBatch {
+    /// The L2 genesis timestamp, used to compute the relative timestamp.
+    pub genesis_timestamp: u64,
+    /// The L2 chain id, used to recover the y parity of legacy transactions.
+    pub chain_id: u64,
+    /// The batches of the L2 blocks in the span.
+    pub batches: Vec<SingularBatch>,
+    /// Whether the first L2 block in the span starts a new epoch.
+    pub origin_changed: bool,
+    /// The encoded size of the transactions of each batch in the span.
+    txs_lens: Vec<SpanTxsLen>,
+    /// The encoded size of the transactions of all batches in the span.
+    total_txs_len: SpanTxsLen,
+}
+
+/// The encoded size of the transactions of one or more L2 blocks in a [SpanBatch].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct SpanTxsLen {
+    /// The number of transactions.
+    txs: usize,
+    /// The number of legacy transactions, which carry a protected bit.
+    legacy_txs: usize,
+    /// The bytes of the block tx counts and of all byte-aligned transaction columns.
+    bytes: usize,
+}
+
+impl SpanTxsLen {
+    fn add(&mut self, other: Self) {
+        self.txs += other.txs;
+        self.legacy_txs += other.legacy_txs;
+        self.bytes += other.bytes;
+    }
+
+    fn sub(&mut self, other: Self) {
+        self.txs -= other.txs;
+        self.legacy_txs -= other.legacy_txs;
+        self.bytes -= other.bytes;
+    }
+}
+
+impl SpanBatch {
+    /// Constructs a new, empty [SpanBatch].
+    pub fn new(genesis_timestamp: u64, chain_id: u64) -> Self {
+        Self {
+            genesis_timestamp,
+            chain_id,
+            ..Self::default()
+        }
+    }
+
+    /// Appends the [SingularBatch] of the next L2 block to the span.
+    ///
+    /// The `sequence_number` is the L2 block's position in its epoch, taken
+    /// from the L1 info deposit. It is only used for the first block in the span.
+    /// Fails if a transaction of the batch cannot be encoded into the span.
+    pub fn append(&mut self, batch: SingularBatch, sequence_number: u64) -> Result<()> {
+        let mut txs_len = SpanTxsLen {
+            bytes: uvarint_len(batch.transactions.len() as u64),
+            ..Default::default()
+        };
+        for tx in &batch.transactions {
+            let tx = SpanTx::decode(tx)?;
+            tx.y_parity(self.chain_id)?;
+            txs_len.txs += 1;
+            txs_len.legacy_txs += (tx.tx_type == 0) as usize;
+            txs_len.bytes += 64
+                + tx.to.map_or(0, |_| 20)
+                + tx.data.len()
+                + uvarint_len(tx.nonce)
+                + uvarint_len(tx.gas);
+        }
+        if self.batches.is_empty() {
+            self.origin_changed = sequence_number == 0;
+        }
+        self.batches.push(batch);
+        self.txs_lens.push(txs_len);
+        self.total_txs_len.add(txs_len);
+        Ok(())
+    }
+
+    /// Removes the last [SingularBatch] from the span.
+    pub fn pop(&mut self) -> Option<SingularBatch> {
+        if let Some(txs_len) = self.txs_lens.pop() {
+            self.total_txs_len.sub(txs_len);
+        }
+        self.batches.pop()
+    }
+
+    /// Returns the number of L2 blocks in the span.
+    pub fn len(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// Returns whether the span contains no L2 blocks.
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// Encodes the span batch as typed batch data.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let first = self.batches.first().ok_or(BatchError::EmptySpanBatch)?;
+        let last = self.batches.last().ok_or(BatchError::EmptySpanBatch)?;
+        let mut out = vec![SPAN_BATCH_TYPE];
+
+        // Prefix
+        let rel_timestamp = first
+            .timestamp
+            .checked_sub(self.genesis_timestamp)
+            .ok_or(BatchError::InvalidBatch)?;
+        write_uvarint(&mut out, rel_timestamp);
+        write_uvarint(&mut out, last.epoch_num);
+        out.extend_from_slice(&first.parent_hash.as_bytes()[..20]);
+        out.extend_from_slice(&last.epoch_hash.as_bytes()[..20]);
+
+        // Payload
+        write_uvarint(&mut out, self.batches.len() as u64);
+        let origin_bits = self
+            .batches
+            .iter()
+            .enumerate()
+            .map(|(i, batch)| match i {
+                0 => self.origin_changed,
+                _ => batch.epoch_num != self.batches[i - 1].epoch_num,
+            })
+            .collect::<Vec<_>>();
+        write_bitlist(&mut out, &origin_bits);
+        for batch in &self.batches {
+            write_uvarint(&mut out, batch.transactions.len() as u64);
+        }
+        self.encode_txs(&mut out)?;
+        Ok(out)
+    }
+
+    /// Encodes the span batch as it is written into a channel: an RLP string of the typed batch data.
+    pub fn rlp_bytes(&self) -> Result<Vec<u8>> {
+        Ok(rlp::encode(&self.encode()?).to_vec())
+    }
+
+    /// Returns the length of [SpanBatch::rlp_bytes] without encoding the span.
+    ///
+    /// The size of the appended transactions is tracked as they are appended,
+    /// so this is cheap enough to check after every appended L2 block.
+    pub fn rlp_len(&self) -> usize {
+        let (Some(first), Some(last)) = (self.batches.first(), self.batches.last())
+        else {
+            return 0
+        };
+        let txs = self.total_txs_len;
+        let len = 1
+            + uvarint_len(first.timestamp.saturating_sub(self.genesis_timestamp))
+            + uvarint_len(last.epoch_num)
+            + 40
+            + uvarint_len(self.batches.len() as u64)
+            + self.batches.len().div_ceil(8)
+            + 2 * txs.txs.div_ceil(8)
+            + txs.bytes
+            + txs.legacy_txs.div_ceil(8);
+        // The typed batch data is wrapped in an RLP string.
+        match len {
+            0..=55 => 1 + len,
+            _ => 1 + (usize::BITS - len.leading_zeros()).div_ceil(8) as usize + len,
+        }
+    }
+
+    /// Encodes the transactions of all blocks in the span as field columns.
+    fn encode_txs(&self, out: &mut Vec<u8>) -> Result<()> {
+        let txs = self
+            .batches
+            .iter()
+            .flat_map(|batch| batch.transactions.iter())
+            .map(|tx| SpanTx::decode(tx))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut contract_creation_bits = Vec::with_capacity(txs.len());
+        let mut y_parity_bits = Vec::with_capacity(txs.len());
+        let mut protected_bits = Vec::new();
+        let mut sigs = Vec::with_capacity(txs.len() * 64);
+        let mut tos = Vec::new();
+        let mut datas = Vec::new();
+        let mut nonces = Vec::new();
+        let mut gases = Vec::new();
+
+        for tx in txs {
+            contract_creation_bits.push(tx.to.is_none());
+            let (y_parity, protected) = tx.y_parity(self.chain_id)?;
+            y_parity_bits.push(y_parity);
+            protected_bits.extend(protected);
+            sigs.extend_from_slice(&u256_bytes(tx.r));
+            sigs.extend_from_slice(&u256_bytes(tx.s));
+            if let Some(to) = tx.to {
+                tos.extend_from_slice(to.as_bytes());
+            }
+            datas.extend(tx.data);
+            write_uvarint(&mut nonces, tx.nonce);
+            write_uvarint(&mut gases, tx.gas);
+        }
+
+        write_bitlist(out, &contract_creation_bits);
+        write_bitlist(out, &y_parity_bits);
+        out.extend(sigs);
+        out.extend(tos);
+        out.extend(datas);
+        out.extend(nonces);
+        out.extend(gases);
+        write_bitlist(out, &protected_bits);
+        Ok(())
+    }
+}
+
+/// The RLP field indices of `(nonce, gas, to, value, data, v, fees, lists)` of a
+/// transaction type, where `lists` are the access and authorization lists.
+type SpanTxLayout = (
+    usize,
+    usize,
+    usize,
+    usize,
+    usize,
+    usize,
+    Range<usize>,
+    Range<usize>,
+);
+
+/// The fields of an EIP-2718 encoded transaction, split into span batch columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SpanTx {
+    /// The EIP-2718 transaction type, where legacy transactions are type 0.
+    tx_type: u8,
+    /// The transaction nonce.
+    nonce: u64,
+    /// The transaction gas limit.
+    gas: u64,
+    /// The recipient, or `None` for contract creations.
+    to: Option<Address>,
+    /// The signature `v` for legacy transactions, or the y parity for typed transactions.
+    v: u64,
+    /// The signature `r`.
+    r: U256,
+    /// The signature `s`.
+    s: U256,
+    /// The remaining fields, encoded as span batch tx data.
+    data: Vec<u8>,
+}
+
+impl SpanTx {
+    /// Decodes the span batch columns from an EIP-2718 encoded transaction.
+    fn decode(raw: &[u8]) -> Result<Self, BatchError> {
+        let (tx_type, body) = match raw.first() {
+            Some(&t) if t <= 0x7f => (t, &raw[1..]),
+            Some(_) => (0, raw),
+            None => return Err(BatchError::InvalidTransaction),
+        };
+
+        // Field indices of [nonce, gas, to, value, data, v], the fee fields
+        // and the trailing list fields per type.
+        let layout = match tx_type {
+            0 => (0, 2, 3, 4, 5, 6, 1..2, 0..0),
+            1 => (1, 3, 4, 5, 6, 8, 2..3, 7..8),
+            2 => (1, 4, 5, 6, 7, 9, 2..4, 8..9),
+            4 => (1, 4, 5, 6, 7, 10, 2..4, 8..10),
+            t => return Err(BatchError::UnsupportedTransactionType(t as u64)),
+        };
+        Self::decode_fields(tx_type, &Rlp::new(body), layout)
+            .map_err(|_| BatchError::InvalidTransaction)
+    }
+
+    /// Decodes the span batch columns from the RLP fields of a transaction.
+    fn decode_fields(
+        tx_type: u8,
+        rlp: &Rlp,
+        layout: SpanTxLayout,
+    ) -> Result<Self, DecoderError> {
+        let (nonce, gas, to, value, data, v, fees, lists) = layout;
+
+        // Span batch tx data is `type ++ rlp([value, fees.., data, access_list?, auth_list?])`.
+        let mut stream = RlpStream::new_list(2 + fees.len() + lists.len());
+        stream.append_raw(rlp.at(value)?.as_raw(), 1);
+        for i in fees {
+            stream.append_raw(rlp.at(i)?.as_raw(), 1);
+        }
+        stream.append_raw(rlp.at(data)?.as_raw(), 1);
+        for i in lists {
+            stream.append_raw(rlp.at(i)?.as_raw(), 1);
+        }
+        let mut tx_data = match tx_type {
+            0 => Vec::new(),
+            t => vec![t],
+        };
+        tx_data.extend_from_slice(&stream.out());
+
+        let to = rlp.at(to)?;
+        Ok(Self {
+            tx_type,
+            nonce: rlp.val_at(nonce)?,
+            gas: rlp.val_at(gas)?,
+            to: match to.is_empty() {
+                true => None,
+                false => Some(to.as_val()?),
+            },
+            v: rlp.val_at(v)?,
+            r: rlp.val_at(v + 1)?,
+            s: rlp.val_at(v + 2)?,
+            data: tx_data,
+        })
+    }
+
+    /// Returns the y parity bit of the signature and, for legacy transactions,
+    /// whether the transaction is replay protected.
+    fn y_parity(&self, chain_id: u64) -> Result<(bool, Option<bool>), BatchError> {
+        if self.tx_type != 0 {
+            return Ok((self.v == 1, None))
+        }
+        let protected = self.v != 27 && self.v != 28;
+        let y_parity = match protected {
+            true => self.v.checked_sub(35 + 2 * chain_id),
+            false => Some(self.v - 27),
+        };
+        match y_parity {
+            Some(y_parity) if y_parity <= 1 => Ok((y_parity == 1, Some(protected))),
+            _ => Err(BatchError::InvalidTransaction),
+        }
+    }
+}
+
+/// Returns the 32-byte big-endian representation of a [U256].
+fn u256_bytes(value: U256) -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    value.to_big_endian(&mut buf);
+    buf
+}
+
+/// Returns the length of an unsigned LEB128 varint.
+fn uvarint_len(value: u64) -> usize {
+    (64 - (value | 1).leading_zeros() as usize).div_ceil(7)
+}
+
+/// Writes an unsigned LEB128 varint.
+pub fn write_uvarint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Writes a bitlist as a big-endian integer padded to `ceil(len / 8)` bytes,
+/// where bit `i` is the `i`-th least significant bit.
+pub fn write_bitlist(out: &mut Vec<u8>, bits: &[bool]) {
+    let len = bits.len().div_ceil(8);
+    let mut bytes = vec![0u8; len];
+    for (i, bit) in bits.iter().enumerate() {
+        if *bit {
+            bytes[len - 1 - i / 8] |= 1 << (i % 8);
+        }
+    }
+    out.extend(bytes);
+}
@@ -8,6 +8,8 @@ use serde::{
     Serialize,
 };
 
+use crate::rollup::BlockNumHash;
+
 /// A block update.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum BlockUpdate {
@@ -28,6 +30,8 @@ pub struct State {
     blocks: Vec<Block<Transaction>>,
     /// Tracks the current block tip
     tip: Option<H256>,
+    /// Whether the state was reset since the pending channels were last cleared
+    reset: bool,
 }
 
 impl State {
@@ -51,9 +55,39 @@ impl State {
         BlockUpdate::Added
     }
 
+    /// Takes all blocks loaded into the [State] since the last call.
+    ///
+    /// The tip is kept so that subsequent blocks are still checked for reorgs.
+    pub fn take_blocks(&mut self) -> Vec<Block<Transaction>> {
+        std::mem::take(&mut self.blocks)
+    }
+
+    /// Drops the blocks up to the given safe head, which the rollup node already derived
+    /// from L1, so that subsequent blocks must extend the safe head.
+    pub fn prune(&mut self, safe_head: BlockNumHash) {
+        self.blocks
+            .retain(|block| block.number.is_some_and(|n| n.as_u64() > safe_head.number));
+        self.tip = match self.blocks.last() {
+            Some(block) => block.hash,
+            None => Some(safe_head.hash),
+        };
+    }
+
     /// Clears the [State] of all blocks and pending channels.
     pub fn clear(&mut self) {
         self.blocks.clear();
         self.tip = None;
     }
+
+    /// Clears the [State] after an L2 reorg and flags the reset,
+    /// so that the [crate::channels::ChannelManager] discards its pending channels.
+    pub fn reset(&mut self) {
+        self.clear();
+        self.reset = true;
+    }
+
+    /// Returns whether the [State] was reset since the last call.
+    pub fn take_reset(&mut self) -> bool {
+        std::mem::take(&mut self.reset)
+    }
 }
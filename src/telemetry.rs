@@ -16,14 +16,17 @@
 //! ### Shutdown
 //!
 //! The shutdown module provides a [shutdown::register_shutdown] function which will
-//! register a ctrl-c handler to gracefully shutdown the running thread.
+//! register a ctrl-c handler to gracefully shutdown the running thread. Hooks registered
+//! with [on_shutdown] run before the process exits.
 
 use std::{
     env::current_dir,
     path::Path,
+    sync::Mutex,
 };
 
 use eyre::Result;
+use once_cell::sync::Lazy;
 use tracing::{
     subscriber::set_global_default,
     Level,
@@ -45,16 +48,33 @@ use ansi_term::Colour::{
     Yellow,
 };
 
+/// A hook run on shutdown
+type ShutdownHook = Box<dyn Fn() + Send>;
+
+/// Hooks run by the ctrl-c handler before the process exits
+static SHUTDOWN_HOOKS: Lazy<Mutex<Vec<ShutdownHook>>> =
+    Lazy::new(|| Mutex::new(Vec::new()));
+
 /// Registers a ctrl-c handler to gracefully shutdown the driver
 pub fn register_shutdown() {
     ctrlc::set_handler(move || {
         println!();
         tracing::info!(target: "archon", "shutting down...");
+        if let Ok(hooks) = SHUTDOWN_HOOKS.lock() {
+            hooks.iter().for_each(|hook| hook());
+        }
         std::process::exit(0);
     })
     .expect("failed to register shutdown handler");
 }
 
+/// Registers a hook that runs on shutdown, before the process exits.
+pub fn on_shutdown(hook: impl Fn() + Send + 'static) {
+    if let Ok(mut hooks) = SHUTDOWN_HOOKS.lock() {
+        hooks.push(Box::new(hook));
+    }
+}
+
 /// Configure logging telemetry
 pub fn init(verbose: bool) -> Result<()> {
     let subscriber = match verbose {
@@ -161,7 +181,7 @@ where
             .metadata()
             .name()
             .split(' ')
-            .last()
+            .next_back()
             .unwrap_or_default();
         let relative_path = current_dir()
             .unwrap_or_default()
@@ -0,0 +1,84 @@
+//! Test Utilities
+//!
+//! Mock L2 blocks shared by the integration tests and benches.
+
+use ethers_core::types::{
+    Block,
+    Bytes,
+    Transaction,
+    H256,
+    U256,
+    U64,
+};
+use rand::RngCore;
+
+use crate::batch::{
+    DEPOSIT_TX_TYPE,
+    L1_INFO_BEDROCK_SELECTOR,
+};
+
+/// Builds the calldata of a Bedrock L1 info deposit.
+pub fn bedrock_l1_info(number: u64, hash: H256, sequence_number: u64) -> Bytes {
+    let mut input = L1_INFO_BEDROCK_SELECTOR.to_vec();
+    let mut word = |value: &[u8]| {
+        let mut w = [0u8; 32];
+        w[32 - value.len()..].copy_from_slice(value);
+        input.extend_from_slice(&w);
+    };
+    word(&number.to_be_bytes());
+    word(&1_700_000_000u64.to_be_bytes());
+    word(&7u64.to_be_bytes());
+    word(hash.as_bytes());
+    word(&sequence_number.to_be_bytes());
+    word(&[0u8; 32]);
+    word(&[0u8; 32]);
+    word(&[0u8; 32]);
+    Bytes::from(input)
+}
+
+/// Builds a mock L2 block with an L1 info deposit and a legacy transaction.
+pub fn mock_block() -> Block<Transaction> {
+    let deposit = Transaction {
+        transaction_type: Some(U64::from(DEPOSIT_TX_TYPE)),
+        input: bedrock_l1_info(17, H256::repeat_byte(0x11), 3),
+        ..Default::default()
+    };
+    let legacy = Transaction {
+        nonce: U256::from(1),
+        gas: U256::from(21_000),
+        gas_price: Some(U256::from(1_000_000_000u64)),
+        value: U256::from(42),
+        v: U64::from(37),
+        r: U256::from(1),
+        s: U256::from(2),
+        ..Default::default()
+    };
+    Block {
+        hash: Some(H256::repeat_byte(0x22)),
+        parent_hash: H256::repeat_byte(0x33),
+        number: Some(U64::from(100)),
+        timestamp: U256::from(1_700_000_002u64),
+        transactions: vec![deposit, legacy],
+        ..Default::default()
+    }
+}
+
+/// Builds a chain of `count` mock L2 blocks starting at `start`.
+///
+/// Each block carries a transaction with `tx_size` bytes of random calldata,
+/// so the channel data does not compress away.
+pub fn mock_chain(start: u64, count: u64, tx_size: usize) -> Vec<Block<Transaction>> {
+    (start..start + count)
+        .map(|number| {
+            let mut block = mock_block();
+            let mut input = vec![0u8; tx_size];
+            rand::thread_rng().fill_bytes(&mut input);
+            block.transactions[1].input = Bytes::from(input);
+            block.number = Some(U64::from(number));
+            block.hash = Some(H256::from_low_u64_be(number));
+            block.parent_hash = H256::from_low_u64_be(number - 1);
+            block.timestamp = U256::from(1_700_000_000 + 2 * number);
+            block
+        })
+        .collect()
+}
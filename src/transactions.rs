@@ -1,12 +1,35 @@
 use crate::{
+    altda::{
+        commitment_tx_data,
+        DaClient,
+    },
+    blobs::{
+        Blob,
+        BlobSidecar,
+        BlobSidecarVersion,
+        BlobTransaction,
+        EthereumKzgCommitter,
+        KzgCommitter,
+        GAS_PER_BLOB,
+        MAX_BLOB_DATA_SIZE,
+    },
+    channels::{
+        TaggedData,
+        TransactionID,
+    },
     client::Archon,
+    nonce::NonceManager,
     pipeline_builder::Stage,
 };
 use bytes::Bytes;
 use ethers_core::types::{
+    transaction::eip2718::TypedTransaction,
     Address,
+    BlockNumber,
+    Eip1559TransactionRequest,
     TransactionReceipt,
-    TransactionRequest,
+    TxHash,
+    U256,
 };
 use ethers_middleware::SignerMiddleware;
 use ethers_providers::{
@@ -14,22 +37,547 @@ use ethers_providers::{
     Middleware,
     Provider,
 };
-use ethers_signers::LocalWallet;
+use ethers_signers::{
+    LocalWallet,
+    Signer,
+};
 use eyre::Result;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 // use once_cell::sync::Lazy;
 use std::{
-    convert::TryFrom,
+    collections::VecDeque,
+    fmt::{
+        self,
+        Display,
+    },
     pin::Pin,
-    sync::mpsc::{
-        channel,
-        Receiver,
-        Sender,
+    str::FromStr,
+    sync::{
+        atomic::{
+            AtomicU64,
+            AtomicUsize,
+            Ordering,
+        },
+        mpsc::{
+            channel,
+            Receiver,
+            Sender,
+        },
+        Arc,
+    },
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+        UNIX_EPOCH,
     },
 };
 
 use crate::errors::TransactionManagerError;
 
-/// A global lock to prevent the [TransactionManager::send_transaction] from being called concurrently.
+/// The default multiplier applied to the L1 base fee to derive the max fee per gas.
+pub const DEFAULT_BASE_FEE_MULTIPLIER: u64 = 2;
+
+/// The default interval after which the fees of a pending transaction are bumped.
+pub const DEFAULT_FEE_BUMP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The default ceiling of the max fee per gas, in gwei.
+pub const DEFAULT_FEE_CEILING_GWEI: u64 = 500;
+
+/// The minimum fee increase of a replacement transaction accepted by the mempool, in percent.
+pub const REPLACEMENT_FEE_BUMP_PERCENT: u64 = 10;
+
+/// The minimum increase of every fee of a replacement blob transaction accepted by the blob
+/// pool, in percent.
+pub const BLOB_REPLACEMENT_FEE_BUMP_PERCENT: u64 = 100;
+
+/// The default maximum number of transactions pending on L1 at once.
+pub const DEFAULT_MAX_PENDING_TRANSACTIONS: usize = 1;
+
+/// The default interval at which receipts of pending transactions are queried.
+pub const DEFAULT_RECEIPT_QUERY_INTERVAL: Duration = Duration::from_secs(12);
+
+/// The default number of L1 confirmations to wait for before a transaction is confirmed.
+pub const DEFAULT_NUM_CONFIRMATIONS: u64 = 6;
+
+/// The default time after which a transaction that was never mined is given up on.
+pub const DEFAULT_RESUBMISSION_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// The number of recent channels whose data availability is remembered by the
+/// [DataAvailabilitySelector].
+pub const MAX_SELECTED_CHANNELS: usize = 64;
+
+/// The maximum time to wait on shutdown for in-flight transactions to be cancelled.
+pub const SHUTDOWN_CANCELLATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The intrinsic gas of a transaction.
+pub const TX_GAS: u64 = 21_000;
+
+/// The gas charged per zero byte of calldata.
+pub const TX_DATA_ZERO_GAS: u64 = 4;
+
+/// The gas charged per non-zero byte of calldata.
+pub const TX_DATA_NON_ZERO_GAS: u64 = 16;
+
+/// The floor gas charged per calldata token, as introduced by EIP-7623.
+pub const TX_FLOOR_GAS_PER_TOKEN: u64 = 10;
+
+/// How frames are made available on L1.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum DataAvailabilityType {
+    /// Frames are posted as the calldata of transactions to the batch inbox.
+    #[default]
+    Calldata,
+    /// Frames are posted as EIP-4844 blobs of transactions to the batch inbox.
+    Blobs,
+    /// Each channel is posted as calldata or blobs, whichever is cheaper.
+    Auto,
+    /// Frames are stored on an alt-DA server and only their commitments are posted as calldata.
+    AltDa,
+}
+
+impl FromStr for DataAvailabilityType {
+    type Err = TransactionManagerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "calldata" => Ok(Self::Calldata),
+            "blobs" => Ok(Self::Blobs),
+            "auto" => Ok(Self::Auto),
+            "alt-da" => Ok(Self::AltDa),
+            _ => Err(TransactionManagerError::UnsupportedDataAvailabilityType),
+        }
+    }
+}
+
+impl Display for DataAvailabilityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Calldata => write!(f, "calldata"),
+            Self::Blobs => write!(f, "blobs"),
+            Self::Auto => write!(f, "auto"),
+            Self::AltDa => write!(f, "alt-da"),
+        }
+    }
+}
+
+/// The estimated L1 costs of posting a frame as calldata or as blobs, in wei.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataAvailabilityCosts {
+    /// The cost of posting the frame as calldata
+    pub calldata: U256,
+    /// The cost of posting the frame as blobs
+    pub blobs: U256,
+}
+
+impl DataAvailabilityCosts {
+    /// Estimates the costs of posting the given frame data at the current L1 fees.
+    ///
+    /// Calldata pays the gas price for the calldata gas. Blobs pay the gas price for the
+    /// intrinsic gas only, plus the blob base fee for the blob gas of the single blob each
+    /// frame is posted in. Frames that do not fit into a blob cannot be posted as blobs.
+    pub fn estimate(data: &[u8], base_fee: U256, tip: U256, blob_base_fee: U256) -> Self {
+        let gas_price = base_fee.saturating_add(tip);
+        let blobs = match data.len() <= MAX_BLOB_DATA_SIZE {
+            true => gas_price
+                .saturating_mul(U256::from(TX_GAS))
+                .saturating_add(blob_base_fee.saturating_mul(U256::from(GAS_PER_BLOB))),
+            false => U256::MAX,
+        };
+        Self {
+            calldata: gas_price.saturating_mul(U256::from(calldata_gas(data))),
+            blobs,
+        }
+    }
+
+    /// Returns the cheaper data availability. Calldata wins ties.
+    pub fn cheapest(&self) -> DataAvailabilityType {
+        if self.blobs < self.calldata {
+            DataAvailabilityType::Blobs
+        } else {
+            DataAvailabilityType::Calldata
+        }
+    }
+}
+
+/// Selects the data availability of each channel when [DataAvailabilityType::Auto] is configured.
+///
+/// All frames of a channel are posted the same way, as selected for its first frame.
+/// The [TransactionManager] only switches between blobs and calldata once no transaction
+/// is in flight.
+#[derive(Debug, Default)]
+pub struct DataAvailabilitySelector {
+    /// The data availability of recent channels, oldest first
+    channels: VecDeque<(String, DataAvailabilityType)>,
+    /// The data availability selected last
+    last: Option<DataAvailabilityType>,
+}
+
+impl DataAvailabilitySelector {
+    /// Constructs a new [DataAvailabilitySelector]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the data availability selected for the channel, if any.
+    pub fn channel(&self, channel_id: &str) -> Option<DataAvailabilityType> {
+        self.channels
+            .iter()
+            .find(|(id, _)| id == channel_id)
+            .map(|(_, selected)| *selected)
+    }
+
+    /// Selects the cheaper data availability for the channel and remembers it.
+    ///
+    /// Switches from the previous selection are logged with the cost comparison.
+    pub fn select(
+        &mut self,
+        channel_id: &str,
+        costs: &DataAvailabilityCosts,
+    ) -> DataAvailabilityType {
+        let selected = costs.cheapest();
+        match self.last {
+            Some(last) if last != selected => {
+                tracing::info!(target: "archon::transactions", "Switching data availability from {} to {} for channel {}: calldata costs {} wei, blobs cost {} wei", last, selected, channel_id, costs.calldata, costs.blobs);
+            }
+            _ => {
+                tracing::debug!(target: "archon::transactions", "Selected {} for channel {}: calldata costs {} wei, blobs cost {} wei", selected, channel_id, costs.calldata, costs.blobs);
+            }
+        }
+        self.last = Some(selected);
+        if self.channels.len() == MAX_SELECTED_CHANNELS {
+            self.channels.pop_front();
+        }
+        self.channels.push_back((channel_id.to_string(), selected));
+        selected
+    }
+}
+
+/// Configures how the [TransactionManager] prices and submits transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionConfig {
+    /// The multiplier applied to the latest L1 base fee to derive the max fee per gas,
+    /// leaving room for the base fee to rise before the transaction is included.
+    pub base_fee_multiplier: u64,
+    /// The interval after which the fees of a pending transaction are bumped.
+    pub fee_bump_interval: Duration,
+    /// The ceiling of the max fee per gas, in wei. Fees are not bumped beyond it.
+    pub fee_ceiling: U256,
+    /// The maximum number of transactions pending on L1 at once, each with its own nonce.
+    pub max_pending_transactions: usize,
+    /// The number of L1 confirmations to wait for before a transaction is confirmed.
+    pub num_confirmations: u64,
+    /// The interval at which receipts of pending transactions are queried.
+    pub receipt_query_interval: Duration,
+    /// The time after which a transaction, and all of its replacements, that was never
+    /// mined is given up on.
+    pub resubmission_timeout: Duration,
+    /// Whether in-flight transactions are cancelled on shutdown or after an L2 reorg reset.
+    pub cancel_pending_transactions: bool,
+    /// How frames are made available on L1.
+    pub data_availability_type: DataAvailabilityType,
+    /// The url of the alt-DA server frames are stored on, if any.
+    pub da_server: Option<String>,
+    /// Whether the gas limit is estimated by L1, for transactions that execute contract code.
+    /// Otherwise the gas limit is the intrinsic gas of the calldata.
+    pub estimate_gas: bool,
+    /// The value sent with every calldata transaction, in wei, e.g. the bond of a dispute game.
+    pub value: U256,
+}
+
+impl Default for TransactionConfig {
+    fn default() -> Self {
+        Self {
+            base_fee_multiplier: DEFAULT_BASE_FEE_MULTIPLIER,
+            fee_bump_interval: DEFAULT_FEE_BUMP_INTERVAL,
+            fee_ceiling: gwei(DEFAULT_FEE_CEILING_GWEI),
+            max_pending_transactions: DEFAULT_MAX_PENDING_TRANSACTIONS,
+            num_confirmations: DEFAULT_NUM_CONFIRMATIONS,
+            receipt_query_interval: DEFAULT_RECEIPT_QUERY_INTERVAL,
+            resubmission_timeout: DEFAULT_RESUBMISSION_TIMEOUT,
+            cancel_pending_transactions: false,
+            data_availability_type: DataAvailabilityType::Calldata,
+            da_server: None,
+            estimate_gas: false,
+            value: U256::zero(),
+        }
+    }
+}
+
+impl TransactionConfig {
+    /// Returns the max fee per gas for the given L1 base fee and priority fee.
+    pub fn max_fee_per_gas(&self, base_fee: U256, tip: U256) -> U256 {
+        base_fee
+            .saturating_mul(U256::from(self.base_fee_multiplier))
+            .saturating_add(tip)
+    }
+
+    /// Returns the max fee per blob gas for the given L1 blob base fee.
+    pub fn max_fee_per_blob_gas(&self, blob_base_fee: U256) -> U256 {
+        blob_base_fee.saturating_mul(U256::from(self.base_fee_multiplier))
+    }
+
+    /// Returns the max fee per blob gas of a replacement blob transaction.
+    ///
+    /// The blob pool only accepts replacements that at least double the max fee per blob gas.
+    pub fn bump_blob_fee(&self, max_fee_per_blob_gas: U256, blob_base_fee: U256) -> U256 {
+        max_fee_per_blob_gas
+            .saturating_mul(U256::from(2))
+            .max(self.max_fee_per_blob_gas(blob_base_fee))
+    }
+
+    /// Returns the fees of a replacement transaction, as `(max_fee_per_gas, max_priority_fee_per_gas)`.
+    ///
+    /// Both fees are raised by at least [REPLACEMENT_FEE_BUMP_PERCENT], or to the currently
+    /// suggested fees if those are higher. Returns `None` if the bumped max fee would exceed
+    /// the fee ceiling, since the mempool would reject a smaller bump.
+    pub fn bump_fees(
+        &self,
+        max_fee: U256,
+        tip: U256,
+        suggested_max_fee: U256,
+        suggested_tip: U256,
+    ) -> Option<(U256, U256)> {
+        self.bump_fees_by(
+            REPLACEMENT_FEE_BUMP_PERCENT,
+            max_fee,
+            tip,
+            suggested_max_fee,
+            suggested_tip,
+        )
+    }
+
+    /// Returns the fees of a replacement blob transaction, like [TransactionConfig::bump_fees].
+    ///
+    /// The blob pool only accepts replacements that at least double every fee, so both fees
+    /// are raised by at least [BLOB_REPLACEMENT_FEE_BUMP_PERCENT]. The max fee per blob gas is
+    /// bumped by [TransactionConfig::bump_blob_fee].
+    pub fn bump_blob_tx_fees(
+        &self,
+        max_fee: U256,
+        tip: U256,
+        suggested_max_fee: U256,
+        suggested_tip: U256,
+    ) -> Option<(U256, U256)> {
+        self.bump_fees_by(
+            BLOB_REPLACEMENT_FEE_BUMP_PERCENT,
+            max_fee,
+            tip,
+            suggested_max_fee,
+            suggested_tip,
+        )
+    }
+
+    /// Raises both fees by at least the given percentage, or to the suggested fees.
+    fn bump_fees_by(
+        &self,
+        percent: u64,
+        max_fee: U256,
+        tip: U256,
+        suggested_max_fee: U256,
+        suggested_tip: U256,
+    ) -> Option<(U256, U256)> {
+        let tip = bump(tip, percent).max(suggested_tip);
+        let max_fee = bump(max_fee, percent).max(suggested_max_fee).max(tip);
+        (max_fee <= self.fee_ceiling).then_some((max_fee, tip))
+    }
+}
+
+/// Raises a fee by the given percentage, rounding up.
+fn bump(fee: U256, percent: u64) -> U256 {
+    let bumped = fee.saturating_mul(U256::from(100 + percent));
+    (bumped + 99) / 100
+}
+
+/// Converts an amount in gwei to wei.
+pub fn gwei(amount: u64) -> U256 {
+    U256::from(amount) * U256::exp10(9)
+}
+
+/// Returns the gas limit of a transaction to an EOA carrying the given calldata.
+///
+/// This is the intrinsic gas, bounded below by the EIP-7623 calldata floor.
+pub fn calldata_gas(data: &[u8]) -> u64 {
+    let zeros = data.iter().filter(|b| **b == 0).count() as u64;
+    let non_zeros = data.len() as u64 - zeros;
+    let standard = TX_GAS + zeros * TX_DATA_ZERO_GAS + non_zeros * TX_DATA_NON_ZERO_GAS;
+    let tokens = zeros + non_zeros * (TX_DATA_NON_ZERO_GAS / TX_DATA_ZERO_GAS);
+    standard.max(TX_GAS + tokens * TX_FLOOR_GAS_PER_TOKEN)
+}
+
+/// The outcome of submitting the transaction of a frame to L1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionOutcome {
+    /// The transaction was included on L1 with the given [TransactionReceipt].
+    Confirmed(TransactionID, Box<TransactionReceipt>),
+    /// The transaction could not be crafted or sent, so the frame must be resubmitted.
+    Failed(TransactionID),
+}
+
+impl TransactionOutcome {
+    /// Returns the [TransactionID] of the submitted frame.
+    pub fn id(&self) -> &TransactionID {
+        match self {
+            Self::Confirmed(id, _) | Self::Failed(id) => id,
+        }
+    }
+}
+
+/// Cancellation
+///
+/// A handle to cancel the transactions the [TransactionManager] has in flight. Each of their
+/// nonces is replaced with a 0-value self-transfer at bumped fees, so that stale frames do
+/// not land on L1 after their channel was discarded.
+///
+/// Clones of a [Cancellation] share the same state, so other stages can request cancellations.
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation {
+    /// The number of cancellation requests so far
+    requests: Arc<AtomicU64>,
+    /// The number of in-flight transactions that are neither cancelled nor completed
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Cancellation {
+    /// Constructs a new [Cancellation] handle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the cancellation of all transactions currently in flight.
+    pub fn cancel(&self) {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Returns the number of in-flight transactions that are neither cancelled nor completed.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Blocks until all transactions in flight are cancelled or completed, or the timeout elapses.
+    ///
+    /// Returns whether no transactions are left in flight.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.in_flight() > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        self.in_flight() == 0
+    }
+
+    /// Tracks a new transaction in flight until the returned [InFlight] is settled or dropped.
+    pub fn track(&self) -> InFlight {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlight {
+            requests: self.requests.clone(),
+            requests_at_start: self.requests.load(Ordering::SeqCst),
+            in_flight: Some(self.in_flight.clone()),
+        }
+    }
+}
+
+/// A transaction in flight, tracked by a [Cancellation].
+#[derive(Debug)]
+pub struct InFlight {
+    /// The cancellation requests of the [Cancellation]
+    requests: Arc<AtomicU64>,
+    /// The number of cancellation requests when the transaction was tracked
+    requests_at_start: u64,
+    /// The in-flight counter of the [Cancellation], until the transaction is settled
+    in_flight: Option<Arc<AtomicUsize>>,
+}
+
+impl InFlight {
+    /// Returns whether a cancellation was requested since the transaction was tracked.
+    pub fn is_cancelled(&self) -> bool {
+        self.requests.load(Ordering::SeqCst) != self.requests_at_start
+    }
+
+    /// Marks the transaction as no longer outstanding, because it was cancelled or completed.
+    pub fn settle(&mut self) {
+        if let Some(in_flight) = self.in_flight.take() {
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.settle();
+    }
+}
+
+/// A transaction published by the [TransactionManager].
+#[derive(Debug, Clone)]
+pub enum BatcherTransaction {
+    /// An EIP-1559 transaction, carrying frames in its calldata or cancelling another transaction
+    Calldata(TypedTransaction),
+    /// An EIP-4844 transaction, carrying frames in its blobs
+    Blob(BlobTransaction),
+}
+
+impl BatcherTransaction {
+    /// Returns the fees of the transaction, as `(max_fee_per_gas, max_priority_fee_per_gas)`.
+    pub fn fees(&self) -> Result<(U256, U256)> {
+        match self {
+            Self::Calldata(TypedTransaction::Eip1559(inner)) => Ok((
+                inner.max_fee_per_gas.unwrap_or_default(),
+                inner.max_priority_fee_per_gas.unwrap_or_default(),
+            )),
+            Self::Blob(tx) => Ok((tx.max_fee_per_gas, tx.max_priority_fee_per_gas)),
+            _ => Err(TransactionManagerError::UnsupportedTransactionType.into()),
+        }
+    }
+
+    /// Sets the fees of the transaction.
+    pub fn set_fees(&mut self, max_fee_per_gas: U256, max_priority_fee_per_gas: U256) {
+        match self {
+            Self::Calldata(TypedTransaction::Eip1559(inner)) => {
+                inner.max_fee_per_gas = Some(max_fee_per_gas);
+                inner.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+            }
+            Self::Blob(tx) => {
+                tx.max_fee_per_gas = max_fee_per_gas;
+                tx.max_priority_fee_per_gas = max_priority_fee_per_gas;
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the nonce of the transaction.
+    pub fn nonce(&self) -> Option<U256> {
+        match self {
+            Self::Calldata(tx) => tx.nonce().copied(),
+            Self::Blob(tx) => Some(tx.nonce),
+        }
+    }
+}
+
+/// The L1 account and settings the [TransactionManager] submits transactions with.
+#[derive(Debug, Clone)]
+pub struct TransactionContext {
+    /// The L1 Chain ID
+    pub l1_chain_id: u64,
+    /// The batch inbox address on L1 to send transactions to
+    pub l1_batch_inbox_address: Address,
+    /// The address to send transactions from
+    pub sender_address: Address,
+    /// Signs transactions for the sender address
+    pub wallet: LocalWallet,
+    /// Configures how transactions are priced and submitted
+    pub transaction_config: TransactionConfig,
+    /// Hands out the nonces of published transactions
+    pub nonce_manager: NonceManager,
+    /// Cancels the transactions in flight
+    pub cancellation: Cancellation,
+    /// Computes the KZG commitments and proofs of blob transactions
+    pub kzg_committer: Arc<dyn KzgCommitter>,
+}
+
+// A global lock to prevent the [TransactionManager::send_transaction] from being called concurrently.
 // static TRANSACTION_MANAGER_LOCK: Lazy<Mutex<i32>> = Lazy::new(|| Mutex::new(0));
 
 /// Transaction Manager
@@ -43,14 +591,23 @@ pub struct TransactionManager {
     sender_address: Option<Address>,
     /// The private key to sign transactions with
     sender_private_key: Option<String>,
+    /// Configures how transactions are priced and submitted
+    transaction_config: TransactionConfig,
+    /// Hands out the nonces of published transactions
+    nonce_manager: NonceManager,
+    /// Cancels the transactions in flight
+    cancellation: Cancellation,
+    /// Computes the KZG commitments and proofs of blob transactions,
+    /// against the [EthereumKzgCommitter] unless set
+    kzg_committer: Option<Arc<dyn KzgCommitter>>,
     /// The [ethers_providers::Provider] to use to send transactions
     provider: Option<Provider<Http>>,
-    /// A channel to send transaction [Receipt]s back to the [crate::client::Archon] orchestrator
-    sender: Option<Sender<Pin<Box<TransactionReceipt>>>>,
-    /// A channel to receive [Bytes] from the [crate::client::Archon] orchestrator
-    receiver: Option<Receiver<Pin<Box<Bytes>>>>,
+    /// A channel to send [TransactionOutcome]s back to the [crate::client::Archon] orchestrator
+    sender: Option<Sender<Pin<Box<TransactionOutcome>>>>,
+    /// A channel to receive [TaggedData] from the [crate::client::Archon] orchestrator
+    receiver: Option<Receiver<Pin<Box<TaggedData>>>>,
     /// A bytes receiver
-    bytes_receiver: Option<Receiver<Pin<Box<Bytes>>>>,
+    bytes_receiver: Option<Receiver<Pin<Box<TaggedData>>>>,
 }
 
 impl TransactionManager {
@@ -74,19 +631,59 @@ impl TransactionManager {
 
     /// Sets the [TransactionManager] sender.
     ///
-    /// This [std::sync::mpsc::channel] is used to send [Receipt]s back to the [crate::client::Archon] orchestrator.
+    /// This [std::sync::mpsc::channel] is used to send [TransactionOutcome]s back to the [crate::client::Archon] orchestrator.
     pub fn with_sender(
         &mut self,
-        sender: Sender<Pin<Box<TransactionReceipt>>>,
+        sender: Sender<Pin<Box<TransactionOutcome>>>,
     ) -> &mut Self {
         self.sender = Some(sender);
         self
     }
 
+    /// Sets the [TransactionConfig] used to price and submit transactions.
+    pub fn with_transaction_config(
+        &mut self,
+        transaction_config: TransactionConfig,
+    ) -> &mut Self {
+        self.transaction_config = transaction_config;
+        self
+    }
+
+    /// Sets the [NonceManager] that hands out transaction nonces.
+    ///
+    /// The [NonceManager] may be shared with other stages, to resync it on L1 reorgs.
+    pub fn with_nonce_manager(&mut self, nonce_manager: NonceManager) -> &mut Self {
+        self.nonce_manager = nonce_manager;
+        self
+    }
+
+    /// Returns a handle on the [NonceManager] that hands out transaction nonces.
+    pub fn nonce_manager(&self) -> NonceManager {
+        self.nonce_manager.clone()
+    }
+
+    /// Sets the [Cancellation] handle used to cancel transactions in flight.
+    ///
+    /// The [Cancellation] may be shared with other stages, to cancel on L2 reorg resets.
+    pub fn with_cancellation(&mut self, cancellation: Cancellation) -> &mut Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Sets the [KzgCommitter] used to commit to the blobs of blob transactions,
+    /// instead of the [EthereumKzgCommitter].
+    pub fn with_kzg_committer(
+        &mut self,
+        kzg_committer: Arc<dyn KzgCommitter>,
+    ) -> &mut Self {
+        self.kzg_committer = Some(kzg_committer);
+        self
+    }
+
     /// Sets the [TransactionManager] bytes receiver.
     pub fn receive_bytes(
         &mut self,
-        bytes_recv: Option<Receiver<Pin<Box<Bytes>>>>,
+        bytes_recv: Option<Receiver<Pin<Box<TaggedData>>>>,
     ) -> &mut Self {
         self.bytes_receiver = bytes_recv;
         self
@@ -95,9 +692,12 @@ impl TransactionManager {
     /// Sets the [TransactionManager] receiver.
     ///
     /// This [std::sync::mpsc::channel] is used by the [crate::client::Archon] orchestrator to send
-    /// [Bytes] messages to the [TransactionManager]. [Bytes] sent through this channel are expected
+    /// [TaggedData] messages to the [TransactionManager]. [TaggedData] sent through this channel is expected
     /// to be the constructed transaction data that should be submitted to L1 built by the [crate::channels::ChannelManager].
-    pub fn with_receiver(&mut self, receiver: Receiver<Pin<Box<Bytes>>>) -> &mut Self {
+    pub fn with_receiver(
+        &mut self,
+        receiver: Receiver<Pin<Box<TaggedData>>>,
+    ) -> &mut Self {
         self.receiver = Some(receiver);
         self
     }
@@ -106,63 +706,206 @@ impl TransactionManager {
     ///
     /// Only sets the receiver channel on the [TransactionManager]
     /// if the provided receiver is `Some`.
-    pub fn receive(&mut self, bytes_recv: Option<Receiver<Pin<Box<Bytes>>>>) {
+    pub fn receive(&mut self, bytes_recv: Option<Receiver<Pin<Box<TaggedData>>>>) {
         if let Some(recv) = bytes_recv {
             self.receiver = Some(recv);
         }
     }
 
-    #[allow(clippy::too_many_arguments)]
     /// Executes the [TransactionManager].
+    ///
+    /// Every frame received is submitted to L1, and its [TransactionOutcome] is sent back.
+    /// Up to [TransactionConfig::max_pending_transactions] frames are pending on L1 at once,
+    /// each with the next nonce handed out by the [NonceManager]. Frames are crafted one at a
+    /// time in the order they are received, so the nonces follow the frame order. The nonce is
+    /// reserved last when crafting. Once a transaction is mined its nonce is settled, and if it
+    /// fails to send its nonce is released to the [NonceManager], to fill the gap it leaves.
+    ///
+    /// With [DataAvailabilityType::Auto], a frame posted differently than the transactions in
+    /// flight waits for all of them to complete before it is crafted.
+    ///
+    /// If enabled, transactions in flight are cancelled through the [Cancellation] handle.
     pub async fn execute(
-        bytes_receiver: Option<Receiver<Pin<Box<Bytes>>>>,
-        l1_chain_id: u64,
-        l1_batch_inbox_address: Address,
-        sender_address: Address,
-        _sender_private_key: String,
+        bytes_receiver: Option<Receiver<Pin<Box<TaggedData>>>>,
+        context: TransactionContext,
         provider: Provider<Http>,
-        receiver: Receiver<Pin<Box<Bytes>>>,
-        sender: Sender<Pin<Box<TransactionReceipt>>>,
+        receiver: Receiver<Pin<Box<TaggedData>>>,
+        sender: Sender<Pin<Box<TransactionOutcome>>>,
     ) -> Result<()> {
-        // TODO: construct the local wallet from a private key
-        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        let TransactionContext {
+            l1_chain_id,
+            l1_batch_inbox_address,
+            sender_address,
+            wallet,
+            transaction_config,
+            nonce_manager,
+            cancellation,
+            kzg_committer,
+        } = context;
+        let mut selector = DataAvailabilitySelector::new();
+        let da_client = transaction_config.da_server.as_deref().map(DaClient::new);
+        let max_pending_transactions = transaction_config.max_pending_transactions.max(1);
+        let pending_slots =
+            Arc::new(tokio::sync::Semaphore::new(max_pending_transactions));
+        // The data availability of the transactions in flight
+        let mut in_flight_data_availability = None;
         loop {
-            // Receive the transaction bytes from the channel
-            let tx_bytes = match &bytes_receiver {
-                Some(bytes_receiver) => bytes_receiver
-                    .recv()
-                    .map_err(|_| TransactionManagerError::ChannelClosed)?,
-                None => receiver
-                    .recv()
-                    .map_err(|_| TransactionManagerError::ChannelClosed)?,
+            // Receive the tagged transaction bytes from the channel.
+            // The receive blocks, so the tasks queued on this worker are handed off meanwhile,
+            // e.g. the sends spawned below.
+            let tagged = tokio::task::block_in_place(|| match &bytes_receiver {
+                Some(bytes_receiver) => bytes_receiver.recv(),
+                None => receiver.recv(),
+            })
+            .map_err(|_| TransactionManagerError::ChannelClosed)?;
+            let id = tagged.id().clone();
+            let tx_bytes = Bytes::from(tagged.data().to_vec());
+
+            // Wait until fewer than the max number of transactions are pending
+            let slot = pending_slots.clone().acquire_owned().await?;
+
+            // Select how the frame is made available
+            let data_availability = match transaction_config.data_availability_type {
+                DataAvailabilityType::Auto => match selector.channel(id.channel_id()) {
+                    Some(selected) => Ok(selected),
+                    None => TransactionManager::estimate_costs(&provider, &tx_bytes)
+                        .await
+                        .map(|costs| selector.select(id.channel_id(), &costs)),
+                },
+                configured => Ok(configured),
             };
-            let tx_bytes = tx_bytes.to_vec();
-            let tx_bytes = Bytes::try_from(tx_bytes)?;
-
-            // Build the transaction from the bytes
-            let built_transaction = if let Ok(tr) = TransactionManager::craft_transaction(
-                l1_chain_id,
-                l1_batch_inbox_address,
-                sender_address,
-                &provider,
-                tx_bytes,
-            )
-            .await
-            {
-                tr
-            } else {
-                tracing::error!(target: "archon::transactions", "Failed to craft transaction");
-                continue
+
+            // L1 does not accept blob and calldata transactions pending from the same sender at
+            // once, so data availability only switches once the transactions in flight drained
+            if let Ok(data_availability) = data_availability {
+                if in_flight_data_availability
+                    .is_some_and(|last| last != data_availability)
+                {
+                    let others = max_pending_transactions - 1;
+                    let in_flight = others - pending_slots.available_permits();
+                    if in_flight > 0 {
+                        tracing::info!(target: "archon::transactions", "Waiting for {} transactions in flight before switching to {}", in_flight, data_availability);
+                        drop(pending_slots.acquire_many(others as u32).await?);
+                    }
+                }
+                in_flight_data_availability = Some(data_availability);
+            }
+
+            // Build the transaction from the bytes with the next nonce
+            let built_transaction = match data_availability {
+                Err(e) => Err(e),
+                Ok(data_availability) => match data_availability {
+                    DataAvailabilityType::Blobs => {
+                        TransactionManager::craft_blob_transaction(
+                            l1_chain_id,
+                            l1_batch_inbox_address,
+                            sender_address,
+                            &provider,
+                            &transaction_config,
+                            &nonce_manager,
+                            kzg_committer.as_ref(),
+                            tx_bytes,
+                        )
+                        .await
+                        .map(BatcherTransaction::Blob)
+                    }
+                    DataAvailabilityType::AltDa => match &da_client {
+                        Some(da_client) => TransactionManager::craft_alt_da_transaction(
+                            l1_chain_id,
+                            l1_batch_inbox_address,
+                            sender_address,
+                            &provider,
+                            &transaction_config,
+                            &nonce_manager,
+                            da_client,
+                            tx_bytes,
+                        )
+                        .await
+                        .map(BatcherTransaction::Calldata),
+                        None => Err(TransactionManagerError::MissingDaServer.into()),
+                    },
+                    DataAvailabilityType::Calldata | DataAvailabilityType::Auto => {
+                        TransactionManager::craft_transaction(
+                            l1_chain_id,
+                            l1_batch_inbox_address,
+                            sender_address,
+                            &provider,
+                            &transaction_config,
+                            &nonce_manager,
+                            tx_bytes,
+                        )
+                        .await
+                        .map(BatcherTransaction::Calldata)
+                    }
+                },
+            };
+            let built_transaction = match built_transaction {
+                Ok(tr) => tr,
+                Err(e) => {
+                    // No nonce is reserved unless crafting succeeds
+                    tracing::error!(target: "archon::transactions", "Failed to craft transaction {}: {:?}", id, e);
+                    sender.send(Box::pin(TransactionOutcome::Failed(id)))?;
+                    continue
+                }
             };
 
-            // Send the transaction to L1
-            let tx_receipt = TransactionManager::send_transaction(
-                provider.clone(),
-                wallet.clone(),
-                built_transaction,
-            )
-            .await?;
-            sender.send(Box::pin(tx_receipt))?;
+            // Send the transaction to L1 without waiting for it to confirm
+            let nonce = built_transaction.nonce().map(|nonce| nonce.as_u64());
+            let provider = provider.clone();
+            let wallet = wallet.clone();
+            let transaction_config = transaction_config.clone();
+            let nonce_manager = nonce_manager.clone();
+            let sender = sender.clone();
+            let mut in_flight = cancellation.track();
+            tokio::spawn(async move {
+                let sent = TransactionManager::send_transaction(
+                    provider,
+                    wallet,
+                    &transaction_config,
+                    built_transaction,
+                    &mut in_flight,
+                )
+                .await;
+                if let Some(nonce) = nonce {
+                    // A mined transaction or cancellation used up its nonce,
+                    // otherwise the nonce is handed back to fill the gap
+                    let used = match &sent {
+                        Ok(_) => true,
+                        Err(e) => matches!(
+                            e.downcast_ref::<TransactionManagerError>(),
+                            Some(TransactionManagerError::TransactionCancelled)
+                        ),
+                    };
+                    let settled = match used {
+                        true => nonce_manager.settle(nonce),
+                        false => nonce_manager.release(nonce),
+                    };
+                    if let Err(e) = settled {
+                        tracing::error!(target: "archon::transactions", "Failed to settle nonce {}: {:?}", nonce, e);
+                    }
+                }
+                let outcome = match sent {
+                    Ok(receipt) => match TransactionManager::check_receipt(
+                        &receipt,
+                        l1_batch_inbox_address,
+                    ) {
+                        Ok(()) => TransactionOutcome::Confirmed(id, Box::new(receipt)),
+                        Err(e) => {
+                            // The mined transaction used up its nonce, so the nonce stays in sync
+                            tracing::error!(target: "archon::transactions", "Transaction {} failed on L1: {}", id, e);
+                            TransactionOutcome::Failed(id)
+                        }
+                    },
+                    Err(e) => {
+                        tracing::error!(target: "archon::transactions", "Failed to send transaction {}: {:?}", id, e);
+                        TransactionOutcome::Failed(id)
+                    }
+                };
+                if sender.send(Box::pin(outcome)).is_err() {
+                    tracing::error!(target: "archon::transactions", "Failed to send transaction outcome");
+                }
+                drop(slot);
+            });
         }
     }
 
@@ -189,15 +932,45 @@ impl TransactionManager {
         let private_key = self
             .sender_private_key
             .ok_or(TransactionManagerError::MissingSenderPrivateKey)?;
+        let wallet =
+            TransactionManager::sender_wallet(&private_key, l1_chain_id, sender_address)?;
         let bytes_receiver = self.bytes_receiver;
+        let transaction_config = self.transaction_config;
+        let nonce_manager = self.nonce_manager;
+        let cancellation = self.cancellation;
+        let kzg_committer = self
+            .kzg_committer
+            .unwrap_or_else(|| Arc::new(EthereumKzgCommitter));
+        if transaction_config.data_availability_type == DataAvailabilityType::AltDa
+            && transaction_config.da_server.is_none()
+        {
+            return Err(TransactionManagerError::MissingDaServer.into())
+        }
+        if transaction_config.cancel_pending_transactions {
+            let cancellation = cancellation.clone();
+            crate::telemetry::on_shutdown(move || {
+                tracing::info!(target: "archon::transactions", "Cancelling {} transactions in flight", cancellation.in_flight());
+                cancellation.cancel();
+                if !cancellation.wait(SHUTDOWN_CANCELLATION_TIMEOUT) {
+                    tracing::warn!(target: "archon::transactions", "{} transactions in flight were not cancelled", cancellation.in_flight());
+                }
+            });
+        }
+        let context = TransactionContext {
+            l1_chain_id,
+            l1_batch_inbox_address,
+            sender_address,
+            wallet,
+            transaction_config,
+            nonce_manager,
+            cancellation,
+            kzg_committer,
+        };
         let transaction_manager_handle = tokio::spawn(async move {
             tracing::info!(target: "archon::transactions", "Spawning transaction manager in new thread...");
             TransactionManager::execute(
                 bytes_receiver,
-                l1_chain_id,
-                l1_batch_inbox_address,
-                sender_address,
-                private_key,
+                context,
                 provider,
                 receiver,
                 sender,
@@ -207,103 +980,497 @@ impl TransactionManager {
         Ok(transaction_manager_handle)
     }
 
+    /// Constructs the [LocalWallet] that signs transactions, bound to the L1 chain id.
+    ///
+    /// Returns an error if the private key does not belong to the sender address.
+    pub fn sender_wallet(
+        private_key: &str,
+        l1_chain_id: u64,
+        sender_address: Address,
+    ) -> Result<LocalWallet> {
+        let private_key = private_key.strip_prefix("0x").unwrap_or(private_key);
+        let wallet = LocalWallet::from_str(private_key)
+            .map_err(|_| TransactionManagerError::InvalidSenderPrivateKey)?
+            .with_chain_id(l1_chain_id);
+        if wallet.address() != sender_address {
+            tracing::error!(target: "archon::transactions", "Sender private key belongs to {:?}, expected {:?}", wallet.address(), sender_address);
+            return Err(TransactionManagerError::SenderAddressMismatch.into())
+        }
+        Ok(wallet)
+    }
+
     /// Sends the given [Transaction] to L1.
     ///
     /// This is used to publish a transaction with incrementally higher gas prices
-    /// until the transaction eventually confirms. This method blocks until an
-    /// invocation of sendTx returns (called with differing gas prices). The method
-    /// may be canceled using the passed context.
+    /// until the transaction eventually confirms. Every fee bump interval, a replacement
+    /// with the same nonce and data is published with its fees raised by at least
+    /// [REPLACEMENT_FEE_BUMP_PERCENT], or [BLOB_REPLACEMENT_FEE_BUMP_PERCENT] for blob
+    /// transactions, until the fee ceiling is reached.
+    ///
+    /// The initially supplied transaction must have gas estimation done, and have a reasonable gas fee.
+    /// Returns the receipt of whichever published transaction was mined, once it has the
+    /// configured number of confirmations. If none of them is mined within the resubmission
+    /// timeout, a [TransactionManagerError::ReceiptTimeout] is returned.
     ///
-    /// The initially supplied transaction must be signed, have gas estimation done, and have a reasonable gas fee.
-    /// When the transaction is resubmitted the tx manager will re-sign the transaction at a different gas pricing
-    /// but retain the gas used, the nonce, and the data.
+    /// If cancellations are enabled and requested for the [InFlight] transaction before it is
+    /// mined, it is replaced with a cancellation at bumped fees. A mined cancellation is
+    /// reported as [TransactionManagerError::TransactionCancelled].
+    ///
+    /// Only an error publishing the initial transaction is returned as is. Once it is published,
+    /// L1 errors are logged and the queries retried, since the transaction may still be mined.
     ///
     /// NOTE: This should be called by AT MOST one caller at a time.
     pub async fn send_transaction(
         provider: Provider<Http>,
         wallet: LocalWallet,
-        tx: TransactionRequest,
+        transaction_config: &TransactionConfig,
+        mut tx: BatcherTransaction,
+        in_flight: &mut InFlight,
     ) -> Result<TransactionReceipt> {
         // Lock the send transaction method
         // let lock_result = TRANSACTION_MANAGER_LOCK
         //     .lock()
         //     .map_err(|_| TransactionManagerError::SendTransactionLocked)?;
 
-        // Set the interval on the provider
-        // let provider = provider.interval(Duration::from_millis(2000u64));
+        // Construct the signer middleware
+        let client = SignerMiddleware::new(provider.clone(), wallet);
+
+        // Publish the transaction and its replacements until one of them confirms
+        let started = Instant::now();
+        let mut published: Vec<TxHash> = Vec::new();
+        // The index of the first published cancellation
+        let mut cancelled_at: Option<usize> = None;
+        let cancel_requested = |in_flight: &InFlight| {
+            transaction_config.cancel_pending_transactions && in_flight.is_cancelled()
+        };
+        let mut resubmit = true;
+        loop {
+            if resubmit {
+                match TransactionManager::publish(&client, &tx).await {
+                    Ok(tx_hash) => {
+                        tracing::info!(target: "archon::transactions", "Published transaction {:?}", tx_hash);
+                        published.push(tx_hash);
+                    }
+                    // The previous transaction may be mined already, so keep waiting for it.
+                    Err(e) if !published.is_empty() => {
+                        tracing::warn!(target: "archon::transactions", "Failed to publish replacement transaction: {:?}", e);
+                    }
+                    Err(e) => return Err(e),
+                }
+                if cancelled_at.is_some() {
+                    in_flight.settle();
+                }
+            }
+
+            // Wait for any published transaction to be mined until the fees are bumped.
+            // Once mined, fees are no longer bumped while it gathers confirmations.
+            let bump_at = Instant::now() + transaction_config.fee_bump_interval;
+            let mut mined = false;
+            while mined || Instant::now() < bump_at {
+                match TransactionManager::find_receipt(&provider, &published).await {
+                    Ok(Some((receipt, confirmations)))
+                        if confirmations >= transaction_config.num_confirmations =>
+                    {
+                        if cancelled_at.is_some_and(|index| {
+                            published[index..].contains(&receipt.transaction_hash)
+                        }) {
+                            tracing::info!(target: "archon::transactions", "Transaction cancelled by {:?}", receipt.transaction_hash);
+                            return Err(
+                                TransactionManagerError::TransactionCancelled.into()
+                            )
+                        }
+                        tracing::info!(target: "archon::transactions", "Transaction {:?} confirmed after {} publications", receipt.transaction_hash, published.len());
+                        return Ok(receipt)
+                    }
+                    Ok(Some(_)) => {
+                        // A mined transaction can no longer be cancelled
+                        mined = true;
+                        if cancel_requested(in_flight) {
+                            in_flight.settle();
+                        }
+                    }
+                    Ok(None) => mined = false,
+                    // The receipts are queried again after the query interval
+                    Err(e) => {
+                        tracing::warn!(target: "archon::transactions", "Failed to query transaction receipts: {:?}", e);
+                    }
+                }
+                if !mined && cancelled_at.is_none() && cancel_requested(in_flight) {
+                    break
+                }
+                if !mined && started.elapsed() >= transaction_config.resubmission_timeout
+                {
+                    tracing::warn!(target: "archon::transactions", "No receipt for any of {} publications after {:?}", published.len(), started.elapsed());
+                    return Err(TransactionManagerError::ReceiptTimeout.into())
+                }
+                tokio::time::sleep(transaction_config.receipt_query_interval).await;
+            }
 
-        // Insert the gas escalator middleware into the provider
-        // let provider = {
-        //     let escalator = GeometricGasPrice::new(5.0, 10u64, None::<u64>);
-        //     GasEscalatorMiddleware::new(provider, escalator, Frequency::PerBlock)
-        // };
+            // Fetch the fee market, waiting another interval for the published
+            // transactions if L1 cannot be queried
+            let (base_fee, suggested_tip, blob_base_fee) =
+                match TransactionManager::fee_market(&provider, &tx).await {
+                    Ok(fee_market) => fee_market,
+                    Err(e) => {
+                        tracing::warn!(target: "archon::transactions", "Failed to query fees, retrying after the bump interval: {:?}", e);
+                        resubmit = false;
+                        tokio::time::sleep(transaction_config.receipt_query_interval)
+                            .await;
+                        continue
+                    }
+                };
 
-        // Construct the signer middleware
-        let client = SignerMiddleware::new(provider, wallet);
+            // Replace the transaction with a cancellation once requested
+            let cancelling = cancelled_at.is_none() && cancel_requested(in_flight);
+            if cancelling {
+                tx = TransactionManager::cancellation_transaction(&tx)?;
+            }
+
+            // Bump the fees of the transaction
+            let (max_fee, tip) = tx.fees()?;
+            let suggested_max_fee =
+                transaction_config.max_fee_per_gas(base_fee, suggested_tip);
+            let bumped = match &tx {
+                BatcherTransaction::Calldata(_) => transaction_config.bump_fees(
+                    max_fee,
+                    tip,
+                    suggested_max_fee,
+                    suggested_tip,
+                ),
+                BatcherTransaction::Blob(_) => transaction_config.bump_blob_tx_fees(
+                    max_fee,
+                    tip,
+                    suggested_max_fee,
+                    suggested_tip,
+                ),
+            };
+            match bumped {
+                Some((max_fee, tip)) => {
+                    tracing::info!(target: "archon::transactions", "Bumping transaction fees to max fee {}, tip {}", max_fee, tip);
+                    tx.set_fees(max_fee, tip);
+                    if let (BatcherTransaction::Blob(blob_tx), Some(blob_base_fee)) =
+                        (&mut tx, blob_base_fee)
+                    {
+                        blob_tx.max_fee_per_blob_gas = transaction_config
+                            .bump_blob_fee(blob_tx.max_fee_per_blob_gas, blob_base_fee);
+                    }
+                    if cancelling {
+                        tracing::info!(target: "archon::transactions", "Cancelling transaction with nonce {:?}", tx.nonce());
+                        cancelled_at = Some(published.len());
+                    }
+                    resubmit = true;
+                }
+                None => {
+                    tracing::warn!(target: "archon::transactions", "Fee ceiling reached, waiting for the pending transaction");
+                    if cancelling {
+                        // The transaction cannot be replaced anymore, so it is not cancelled
+                        in_flight.settle();
+                    }
+                    resubmit = false;
+                }
+            }
+        }
+    }
 
-        // Send the transaction
-        let pending_tx = client.send_transaction(tx, None).await?;
-        let receipt = pending_tx.confirmations(6).await?;
-        let receipt =
-            receipt.ok_or(TransactionManagerError::TransactionReceiptNotFound)?;
+    /// Publishes the given [BatcherTransaction], returning its hash.
+    ///
+    /// Blob transactions are signed and encoded here, since `ethers` does not support them.
+    pub async fn publish(
+        client: &SignerMiddleware<Provider<Http>, LocalWallet>,
+        tx: &BatcherTransaction,
+    ) -> Result<TxHash> {
+        match tx {
+            BatcherTransaction::Calldata(tx) => {
+                Ok(*client.send_transaction(tx.clone(), None).await?)
+            }
+            BatcherTransaction::Blob(tx) => {
+                let signature = client.signer().sign_hash(tx.sighash());
+                let pending_tx = client
+                    .inner()
+                    .send_raw_transaction(tx.rlp_signed(&signature).into())
+                    .await?;
+                Ok(*pending_tx)
+            }
+        }
+    }
+
+    /// Returns a 0-value self-transfer that replaces the nonce of the given transaction.
+    ///
+    /// Blob transactions can only be replaced by blob transactions,
+    /// so they are cancelled with a single zero blob in the same sidecar version.
+    ///
+    /// The fees of the given transaction are kept, so they must still be bumped
+    /// for the cancellation to replace it in the mempool.
+    pub fn cancellation_transaction(
+        tx: &BatcherTransaction,
+    ) -> Result<BatcherTransaction> {
+        let inner = match tx {
+            BatcherTransaction::Calldata(TypedTransaction::Eip1559(inner)) => inner,
+            BatcherTransaction::Blob(tx) => {
+                return Ok(BatcherTransaction::Blob(BlobTransaction {
+                    to: tx.from,
+                    value: U256::zero(),
+                    data: Bytes::new(),
+                    gas: U256::from(TX_GAS),
+                    sidecar: BlobSidecar::empty(tx.sidecar.version),
+                    ..tx.clone()
+                }))
+            }
+            _ => return Err(TransactionManagerError::UnsupportedTransactionType.into()),
+        };
+        let sender = inner
+            .from
+            .ok_or(TransactionManagerError::MissingSenderAddress)?;
+        let mut cancellation = Eip1559TransactionRequest::new()
+            .from(sender)
+            .to(sender)
+            .value(0)
+            .gas(TX_GAS);
+        cancellation.chain_id = inner.chain_id;
+        cancellation.nonce = inner.nonce;
+        cancellation.max_fee_per_gas = inner.max_fee_per_gas;
+        cancellation.max_priority_fee_per_gas = inner.max_priority_fee_per_gas;
+        Ok(BatcherTransaction::Calldata(cancellation.into()))
+    }
 
-        // Force drop the lock result to demonstrate we are done sending the transaction
-        // std::mem::drop(lock_result);
+    /// Checks that a mined transaction succeeded and was sent to the batch inbox.
+    ///
+    /// Frames of transactions failing this check are not derived from L1,
+    /// so they must be resubmitted.
+    pub fn check_receipt(
+        receipt: &TransactionReceipt,
+        l1_batch_inbox_address: Address,
+    ) -> std::result::Result<(), TransactionManagerError> {
+        if receipt.status != Some(1u64.into()) {
+            return Err(TransactionManagerError::TransactionReverted)
+        }
+        if receipt.to != Some(l1_batch_inbox_address) {
+            return Err(TransactionManagerError::UnexpectedRecipient(receipt.to))
+        }
+        Ok(())
+    }
 
-        // Return the receipt
-        Ok(receipt)
+    /// Returns the receipt of the first given transaction that was mined,
+    /// along with its number of L1 confirmations.
+    pub async fn find_receipt(
+        provider: &Provider<Http>,
+        tx_hashes: &[TxHash],
+    ) -> Result<Option<(TransactionReceipt, u64)>> {
+        let head = provider.get_block_number().await?.as_u64();
+        for tx_hash in tx_hashes {
+            let Some(receipt) = provider.get_transaction_receipt(*tx_hash).await? else {
+                continue
+            };
+            let included = receipt
+                .block_number
+                .ok_or(TransactionManagerError::TransactionReceiptNotFound)?
+                .as_u64();
+            let confirmations = (head + 1).saturating_sub(included);
+            return Ok(Some((receipt, confirmations)))
+        }
+        Ok(None)
     }
 
-    /// Crafts a transaction from the given [Bytes].
-    /// This queries L1 for the current fee market conditions
-    /// as well as for the nonce.
+    /// Crafts an EIP-1559 transaction from the given [Bytes].
+    /// This queries L1 for the current fee market conditions,
+    /// and takes the nonce from the [NonceManager].
+    ///
+    /// The max fee per gas is the latest base fee scaled by the configured multiplier,
+    /// plus the priority fee from `eth_maxPriorityFeePerGas`. The gas limit is the
+    /// intrinsic gas of the calldata.
+    ///
     /// NOTE: This method SHOULD NOT publish the resulting transaction.
     pub async fn craft_transaction(
         l1_chain_id: u64,
         l1_batch_inbox_address: Address,
         sender: Address,
         provider: &Provider<Http>,
+        transaction_config: &TransactionConfig,
+        nonce_manager: &NonceManager,
         bytes: Bytes,
-    ) -> Result<TransactionRequest> {
-        // Get the current nonce and gas price
-        let nonce = provider.get_transaction_count(sender, None).await?;
-        let gas_price = provider.get_gas_price().await?;
+    ) -> Result<TypedTransaction> {
+        // Get the fee market conditions
+        let (base_fee, tip) = TransactionManager::suggest_fees(provider).await?;
+        let max_fee_per_gas = transaction_config.max_fee_per_gas(base_fee, tip);
 
         // Create the transaction
-        let tx = TransactionRequest::new()
+        let mut tx: TypedTransaction = Eip1559TransactionRequest::new()
             .chain_id(l1_chain_id)
+            .from(sender)
             .to(l1_batch_inbox_address)
-            .data(bytes)
-            .gas_price(gas_price)
-            .nonce(nonce);
+            .data(bytes.clone())
+            .value(transaction_config.value)
+            .max_fee_per_gas(max_fee_per_gas)
+            .max_priority_fee_per_gas(tip)
+            .into();
+        let gas = match transaction_config.estimate_gas {
+            true => provider.estimate_gas(&tx, None).await?,
+            false => U256::from(calldata_gas(&bytes)),
+        };
+        tx.set_gas(gas);
+
+        // Take the next nonce last, so that a failure above does not skip a nonce
+        let nonce = nonce_manager.next_nonce(provider, sender).await?;
+        tx.set_nonce(nonce);
+        tracing::debug!(target: "archon::transactions", "Crafted transaction with nonce {}, base fee {}, tip {}, max fee {}, gas {}", nonce, base_fee, tip, max_fee_per_gas, gas);
 
         Ok(tx)
     }
+
+    /// Crafts an EIP-1559 transaction posting the alt-DA commitment of the given [Bytes].
+    ///
+    /// The bytes are stored on the alt-DA server first. The transaction data is the
+    /// commitment returned by the server, prefixed with the alt-DA derivation version.
+    ///
+    /// NOTE: This method SHOULD NOT publish the resulting transaction.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn craft_alt_da_transaction(
+        l1_chain_id: u64,
+        l1_batch_inbox_address: Address,
+        sender: Address,
+        provider: &Provider<Http>,
+        transaction_config: &TransactionConfig,
+        nonce_manager: &NonceManager,
+        da_client: &DaClient,
+        bytes: Bytes,
+    ) -> Result<TypedTransaction> {
+        let commitment = da_client.set_input(&bytes).await?;
+        TransactionManager::craft_transaction(
+            l1_chain_id,
+            l1_batch_inbox_address,
+            sender,
+            provider,
+            transaction_config,
+            nonce_manager,
+            commitment_tx_data(&commitment),
+        )
+        .await
+    }
+
+    /// Crafts an EIP-4844 transaction carrying the given [Bytes] in a blob.
+    ///
+    /// The max fee per blob gas is the L1 blob base fee scaled by the base fee multiplier.
+    /// Since the calldata is empty, the gas limit is the intrinsic gas.
+    ///
+    /// NOTE: This method SHOULD NOT publish the resulting transaction.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn craft_blob_transaction(
+        l1_chain_id: u64,
+        l1_batch_inbox_address: Address,
+        sender: Address,
+        provider: &Provider<Http>,
+        transaction_config: &TransactionConfig,
+        nonce_manager: &NonceManager,
+        kzg_committer: &dyn KzgCommitter,
+        bytes: Bytes,
+    ) -> Result<BlobTransaction> {
+        // The mempool only accepts cell proofs once L1 activated Osaka
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let sidecar = BlobSidecar::new(
+            BlobSidecarVersion::at(l1_chain_id, now),
+            vec![Blob::from_data(&bytes)?],
+            kzg_committer,
+        )?;
+
+        // Get the fee market conditions and the next nonce
+        let (base_fee, tip) = TransactionManager::suggest_fees(provider).await?;
+        let blob_base_fee = TransactionManager::blob_base_fee(provider).await?;
+        let nonce = nonce_manager.next_nonce(provider, sender).await?;
+        let max_fee_per_gas = transaction_config.max_fee_per_gas(base_fee, tip);
+        let max_fee_per_blob_gas = transaction_config.max_fee_per_blob_gas(blob_base_fee);
+        tracing::debug!(target: "archon::transactions", "Crafting blob transaction with nonce {}, base fee {}, tip {}, blob base fee {}", nonce, base_fee, tip, blob_base_fee);
+
+        Ok(BlobTransaction {
+            chain_id: l1_chain_id,
+            from: sender,
+            nonce: nonce.into(),
+            max_priority_fee_per_gas: tip,
+            max_fee_per_gas,
+            gas: U256::from(TX_GAS),
+            to: l1_batch_inbox_address,
+            value: U256::zero(),
+            data: Bytes::new(),
+            max_fee_per_blob_gas,
+            sidecar,
+        })
+    }
+
+    /// Estimates the costs of posting the frame data as calldata or as blobs at the current L1 fees.
+    pub async fn estimate_costs(
+        provider: &Provider<Http>,
+        data: &[u8],
+    ) -> Result<DataAvailabilityCosts> {
+        let (base_fee, tip) = TransactionManager::suggest_fees(provider).await?;
+        let blob_base_fee = TransactionManager::blob_base_fee(provider).await?;
+        Ok(DataAvailabilityCosts::estimate(
+            data,
+            base_fee,
+            tip,
+            blob_base_fee,
+        ))
+    }
+
+    /// Fetches the current L1 blob base fee.
+    pub async fn blob_base_fee(provider: &Provider<Http>) -> Result<U256> {
+        Ok(provider.request("eth_blobBaseFee", ()).await?)
+    }
+
+    /// Fetches the fees to bump the given [BatcherTransaction] to: the latest L1 base fee, the
+    /// suggested priority fee and, for blob transactions, the blob base fee.
+    pub async fn fee_market(
+        provider: &Provider<Http>,
+        tx: &BatcherTransaction,
+    ) -> Result<(U256, U256, Option<U256>)> {
+        let (base_fee, tip) = TransactionManager::suggest_fees(provider).await?;
+        let blob_base_fee = match tx {
+            BatcherTransaction::Blob(_) => {
+                Some(TransactionManager::blob_base_fee(provider).await?)
+            }
+            BatcherTransaction::Calldata(_) => None,
+        };
+        Ok((base_fee, tip, blob_base_fee))
+    }
+
+    /// Fetches the latest L1 base fee and the suggested priority fee.
+    pub async fn suggest_fees(provider: &Provider<Http>) -> Result<(U256, U256)> {
+        let base_fee = provider
+            .get_block(BlockNumber::Latest)
+            .await?
+            .and_then(|block| block.base_fee_per_gas)
+            .ok_or(TransactionManagerError::MissingBaseFee)?;
+        let tip: U256 = provider.request("eth_maxPriorityFeePerGas", ()).await?;
+        Ok((base_fee, tip))
+    }
 }
 
 impl Stage for TransactionManager {
-    type Input = Bytes;
-    type Output = TransactionReceipt;
+    type Input = TaggedData;
+    type Output = TransactionOutcome;
     fn build(
         &mut self,
         pipeline: &mut Archon,
-        receiver: Option<Receiver<Pin<Box<Bytes>>>>,
-    ) -> Result<Receiver<Pin<Box<TransactionReceipt>>>> {
-        let (archon_sender, tx_mgr_receiver) = channel::<Pin<Box<Bytes>>>();
-        let (tx_mgr_sender, archon_receiver) = channel::<Pin<Box<TransactionReceipt>>>();
+        receiver: Option<Receiver<Pin<Box<TaggedData>>>>,
+    ) -> Result<Receiver<Pin<Box<TransactionOutcome>>>> {
+        let (archon_sender, tx_mgr_receiver) = channel::<Pin<Box<TaggedData>>>();
+        let (tx_mgr_sender, archon_receiver) = channel::<Pin<Box<TransactionOutcome>>>();
         pipeline.with_tx_manager_sender(archon_sender.clone());
         // self.tx_manager_receiver = Some(archon_receiver.clone());
         // let transaction_manager = pipeline.tx_manager.take();
         let mut transaction_manager = TransactionManager::new(
-            Some(pipeline.config().network.into()),
+            Some(pipeline.config().l1_chain_id()),
             Some(pipeline.config().batcher_inbox),
-            Some(pipeline.config().proposer_address),
+            Some(pipeline.config().batcher_address),
             Some(pipeline.config().batcher_private_key.clone()),
             pipeline.config().get_l1_client()?,
         );
+        transaction_manager
+            .with_transaction_config(pipeline.config().transaction_config());
+        transaction_manager.with_nonce_manager(pipeline.nonce_manager());
+        transaction_manager.with_cancellation(pipeline.cancellation());
         transaction_manager.with_sender(tx_mgr_sender);
         transaction_manager.with_receiver(tx_mgr_receiver);
         transaction_manager.receive_bytes(receiver);
+        pipeline.with_transaction_manager(transaction_manager);
 
         Ok(archon_receiver)
     }
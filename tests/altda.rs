@@ -0,0 +1,147 @@
+use std::{
+    str::FromStr,
+    sync::mpsc,
+};
+
+use archon::{
+    altda::{
+        commitment_tx_data,
+        DaClient,
+    },
+    builder::DERIVATION_VERSION_1,
+    errors::{
+        AltDaError,
+        TransactionManagerError,
+    },
+    nonce::NonceManager,
+    transactions::{
+        DataAvailabilityType,
+        TransactionConfig,
+        TransactionManager,
+    },
+};
+use bytes::Bytes;
+use ethers_core::types::{
+    Address,
+    U256,
+};
+use ethers_providers::{
+    Http,
+    Provider,
+};
+use ethers_signers::{
+    LocalWallet,
+    Signer,
+};
+use serde_json::json;
+
+mod common;
+
+use common::da_server::DaServer;
+
+const SENDER_PRIVATE_KEY: &str =
+    "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72";
+
+#[test]
+fn test_data_availability_type_alt_da() {
+    assert_eq!(
+        DataAvailabilityType::from_str("alt-da").unwrap(),
+        DataAvailabilityType::AltDa
+    );
+    assert_eq!(DataAvailabilityType::AltDa.to_string(), "alt-da");
+}
+
+#[test]
+fn test_commitment_tx_data() {
+    let tx_data = commitment_tx_data(&[0x00, 0xab, 0xcd]);
+    assert_eq!(tx_data.as_ref(), &[DERIVATION_VERSION_1, 0x00, 0xab, 0xcd]);
+}
+
+#[tokio::test]
+async fn test_set_input() {
+    let server = DaServer::default();
+    let da_client = DaClient::new(&format!("{}/", server.serve().await));
+
+    let commitment = da_client.set_input(b"frame data").await.unwrap();
+    assert_eq!(commitment.as_ref(), DaServer::commitment(b"frame data"));
+    assert_eq!(server.input(&commitment).unwrap(), b"frame data");
+}
+
+#[tokio::test]
+async fn test_set_input_failure() {
+    let server = DaServer::default().failing_with(503);
+    let da_client = DaClient::new(&server.serve().await);
+
+    let err = da_client.set_input(b"frame data").await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<AltDaError>(),
+        Some(AltDaError::UnexpectedStatus(503))
+    ));
+}
+
+#[tokio::test]
+async fn test_craft_alt_da_transaction() {
+    let server = DaServer::default();
+    let da_client = DaClient::new(&server.serve().await);
+    let devnet = common::devnet::Devnet::default()
+        .with_result(
+            "eth_getBlockByNumber",
+            json!({ "number": "0x10", "baseFeePerGas": "0x9502f900" }),
+        )
+        .with_result("eth_maxPriorityFeePerGas", json!("0x3b9aca00"))
+        .with_result("eth_getTransactionCount", json!("0x7"));
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let sender = LocalWallet::from_str(SENDER_PRIVATE_KEY).unwrap().address();
+    let inbox = Address::repeat_byte(0xff);
+
+    let tx = TransactionManager::craft_alt_da_transaction(
+        5,
+        inbox,
+        sender,
+        &provider,
+        &TransactionConfig::default(),
+        &NonceManager::new(),
+        &da_client,
+        Bytes::from_static(b"frame data"),
+    )
+    .await
+    .unwrap();
+
+    // Only the commitment is posted to the batch inbox
+    let commitment = DaServer::commitment(b"frame data");
+    assert_eq!(
+        tx.data().unwrap().as_ref(),
+        commitment_tx_data(&commitment).as_ref()
+    );
+    assert_eq!(tx.to_addr(), Some(&inbox));
+    assert_eq!(tx.nonce(), Some(&U256::from(7)));
+    assert_eq!(server.input(&commitment).unwrap(), b"frame data");
+}
+
+#[tokio::test]
+async fn test_spawn_alt_da_without_server() {
+    let sender = LocalWallet::from_str(SENDER_PRIVATE_KEY).unwrap().address();
+    let provider = Provider::<Http>::try_from("http://127.0.0.1:8545").unwrap();
+    let mut transaction_manager = TransactionManager::new(
+        Some(5),
+        Some(Address::repeat_byte(0xff)),
+        Some(sender),
+        Some(SENDER_PRIVATE_KEY.to_string()),
+        provider,
+    );
+    let (sender, _) = mpsc::channel();
+    let (_, receiver) = mpsc::channel();
+    transaction_manager
+        .with_sender(sender)
+        .with_receiver(receiver)
+        .with_transaction_config(TransactionConfig {
+            data_availability_type: DataAvailabilityType::AltDa,
+            ..Default::default()
+        });
+
+    let err = transaction_manager.spawn().unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<TransactionManagerError>(),
+        Some(TransactionManagerError::MissingDaServer)
+    ));
+}
@@ -0,0 +1,315 @@
+use std::io::Read;
+
+use archon::{
+    batch::{
+        L1BlockInfo,
+        SingularBatch,
+        DEPOSIT_TX_TYPE,
+        L1_INFO_ECOTONE_SELECTOR,
+        L1_INFO_ISTHMUS_SELECTOR,
+        SET_CODE_TX_TYPE,
+    },
+    builder::ChannelOut,
+    errors::BatchError,
+    span_batch::{
+        SpanBatch,
+        SPAN_BATCH_TYPE,
+    },
+    test_utils::mock_block,
+};
+use ethers_core::{
+    types::{
+        Address,
+        Block,
+        Transaction,
+        H256,
+        U64,
+    },
+    utils::{
+        keccak256,
+        rlp::{
+            self,
+            Rlp,
+        },
+    },
+};
+use flate2::read::ZlibDecoder;
+use serde_json::json;
+
+/// Builds an EIP-7702 set code transaction as returned by `eth_getBlockByNumber`.
+fn set_code_tx() -> Transaction {
+    serde_json::from_value(json!({
+        "hash": format!("{:?}", H256::repeat_byte(0x77)),
+        "nonce": "0x7",
+        "blockHash": null,
+        "blockNumber": null,
+        "transactionIndex": null,
+        "from": format!("{:?}", Address::repeat_byte(0x01)),
+        "to": format!("{:?}", Address::repeat_byte(0x02)),
+        "value": "0x0",
+        "gas": "0x186a0",
+        "input": "0xdeadbeef",
+        "type": "0x4",
+        "chainId": "0xa",
+        "maxPriorityFeePerGas": "0x3b9aca00",
+        "maxFeePerGas": "0x77359400",
+        "accessList": [],
+        "authorizationList": [{
+            "chainId": "0xa",
+            "address": format!("{:?}", Address::repeat_byte(0x03)),
+            "nonce": "0x8",
+            "yParity": "0x1",
+            "r": "0x5",
+            "s": "0x6",
+        }],
+        "v": "0x1",
+        "yParity": "0x1",
+        "r": "0x3",
+        "s": "0x4",
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_singular_batch_from_block() {
+    let block = mock_block();
+    let batch = SingularBatch::from_block(&block).unwrap();
+    assert_eq!(batch.parent_hash, block.parent_hash);
+    assert_eq!(batch.epoch_num, 17);
+    assert_eq!(batch.epoch_hash, H256::repeat_byte(0x11));
+    assert_eq!(batch.timestamp, 1_700_000_002);
+    assert_eq!(batch.transactions, vec![block.transactions[1].rlp()]);
+}
+
+#[test]
+fn test_singular_batch_encodes_set_code_transactions() {
+    let mut block = mock_block();
+    block.transactions[1] = set_code_tx();
+    let batch = SingularBatch::from_block(&block).unwrap();
+
+    // ethers would encode the transaction as a legacy transaction
+    let tx = &batch.transactions[0];
+    assert_ne!(*tx, block.transactions[1].rlp());
+    assert_eq!(tx[0], SET_CODE_TX_TYPE as u8);
+    let fields = Rlp::new(&tx[1..]);
+    assert_eq!(fields.item_count().unwrap(), 13);
+    assert_eq!(fields.val_at::<u64>(0).unwrap(), 10);
+    assert_eq!(fields.val_at::<u64>(1).unwrap(), 7);
+    assert_eq!(
+        fields.val_at::<Vec<u8>>(7).unwrap(),
+        vec![0xde, 0xad, 0xbe, 0xef]
+    );
+    let authorization = fields.at(9).unwrap().at(0).unwrap();
+    assert_eq!(
+        authorization.val_at::<Address>(1).unwrap(),
+        Address::repeat_byte(0x03)
+    );
+    assert_eq!(authorization.val_at::<u64>(2).unwrap(), 8);
+    assert_eq!(fields.val_at::<u64>(10).unwrap(), 1);
+    assert_eq!(fields.val_at::<u64>(12).unwrap(), 4);
+}
+
+#[test]
+fn test_singular_batch_rejects_unknown_transaction_types() {
+    let mut block = mock_block();
+    block.transactions[1].transaction_type = Some(U64::from(5));
+    let err = SingularBatch::from_block(&block).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<BatchError>(),
+        Some(BatchError::UnsupportedTransactionType(5))
+    ));
+}
+
+#[test]
+fn test_singular_batch_round_trip() {
+    let batch = SingularBatch::from_block(&mock_block()).unwrap();
+    let encoded = batch.encode();
+    assert_eq!(encoded[0], 0x00);
+    assert_eq!(SingularBatch::decode(&encoded).unwrap(), batch);
+    assert_eq!(
+        SingularBatch::decode_rlp_bytes(&batch.rlp_bytes()).unwrap(),
+        batch
+    );
+}
+
+#[test]
+fn test_singular_batch_requires_l1_info_deposit() {
+    let mut block = mock_block();
+    block.transactions.remove(0);
+    assert!(SingularBatch::from_block(&block).is_err());
+}
+
+#[test]
+fn test_l1_info_ecotone() {
+    let mut input = L1_INFO_ECOTONE_SELECTOR.to_vec();
+    input.extend_from_slice(&[0u8; 8]);
+    input.extend_from_slice(&5u64.to_be_bytes());
+    input.extend_from_slice(&1_700_000_000u64.to_be_bytes());
+    input.extend_from_slice(&99u64.to_be_bytes());
+    input.extend_from_slice(&[0u8; 64]);
+    input.extend_from_slice(H256::repeat_byte(0x44).as_bytes());
+    input.extend_from_slice(&[0u8; 32]);
+
+    let info = L1BlockInfo::from_deposit_input(&input).unwrap();
+    assert_eq!(info.number, 99);
+    assert_eq!(info.timestamp, 1_700_000_000);
+    assert_eq!(info.sequence_number, 5);
+    assert_eq!(info.hash, H256::repeat_byte(0x44));
+}
+
+#[test]
+fn test_l1_info_isthmus() {
+    assert_eq!(
+        L1_INFO_ISTHMUS_SELECTOR,
+        keccak256("setL1BlockValuesIsthmus()")[..4]
+    );
+    // The packed `setL1BlockValuesIsthmus` calldata, field by field as op-geth writes it
+    let mut input = L1_INFO_ISTHMUS_SELECTOR.to_vec();
+    input.extend_from_slice(&0x146bu32.to_be_bytes());
+    input.extend_from_slice(&0xf79c5u32.to_be_bytes());
+    input.extend_from_slice(&2u64.to_be_bytes());
+    input.extend_from_slice(&1_746_806_159u64.to_be_bytes());
+    input.extend_from_slice(&22_431_504u64.to_be_bytes());
+    input.extend_from_slice(H256::from_low_u64_be(0x4798_9ab9).as_bytes());
+    input.extend_from_slice(H256::from_low_u64_be(1).as_bytes());
+    input.extend_from_slice(H256::repeat_byte(0x44).as_bytes());
+    input.extend_from_slice(H256::repeat_byte(0x55).as_bytes());
+    input.extend_from_slice(&0xaabb_ccddu32.to_be_bytes());
+    input.extend_from_slice(&0x1122_3344_5566_7788u64.to_be_bytes());
+    assert_eq!(input.len(), 176);
+
+    let deposit = Transaction {
+        transaction_type: Some(U64::from(DEPOSIT_TX_TYPE)),
+        input: input.clone().into(),
+        ..Default::default()
+    };
+    let block = Block {
+        transactions: vec![deposit],
+        ..Default::default()
+    };
+    let info = L1BlockInfo::from_block(&block).unwrap();
+    assert_eq!(info.number, 22_431_504);
+    assert_eq!(info.timestamp, 1_746_806_159);
+    assert_eq!(info.sequence_number, 2);
+    assert_eq!(info.hash, H256::repeat_byte(0x44));
+
+    // The Ecotone length is rejected for the Isthmus selector
+    assert!(L1BlockInfo::from_deposit_input(&input[..164]).is_err());
+}
+
+#[test]
+fn test_span_batch_encoding() {
+    let block = mock_block();
+    let first = SingularBatch::from_block(&block).unwrap();
+    let mut second = first.clone();
+    second.parent_hash = block.hash.unwrap();
+    second.timestamp += 2;
+    second.epoch_num += 1;
+    second.epoch_hash = H256::repeat_byte(0x55);
+
+    let mut span = SpanBatch::new(1_700_000_000, 1);
+    span.append(first.clone(), 3).unwrap();
+    span.append(second.clone(), 0).unwrap();
+    let encoded = span.encode().unwrap();
+
+    let mut expected = vec![SPAN_BATCH_TYPE];
+    // rel_timestamp = 2, l1_origin_num = 18
+    expected.extend_from_slice(&[0x02, 0x12]);
+    expected.extend_from_slice(&first.parent_hash.as_bytes()[..20]);
+    expected.extend_from_slice(&second.epoch_hash.as_bytes()[..20]);
+    // block_count = 2, origin_bits = 0b10, block_tx_counts = [1, 1]
+    expected.extend_from_slice(&[0x02, 0x02, 0x01, 0x01]);
+    // contract_creation_bits = 0b11, y_parity_bits = 0b00
+    expected.extend_from_slice(&[0x03, 0x00]);
+    assert_eq!(&encoded[..expected.len()], expected.as_slice());
+    // protected_bits = 0b11
+    assert_eq!(encoded[encoded.len() - 1], 0x03);
+}
+
+#[test]
+fn test_span_batch_is_smaller_than_singular_batches() {
+    let batch = SingularBatch::from_block(&mock_block()).unwrap();
+    let mut span = SpanBatch::new(1_700_000_000, 1);
+    let mut singular_len = 0;
+    for _ in 0..10 {
+        span.append(batch.clone(), 0).unwrap();
+        singular_len += batch.rlp_bytes().len();
+    }
+    assert!(span.rlp_bytes().unwrap().len() < singular_len);
+}
+
+#[test]
+fn test_span_batch_encodes_set_code_transactions() {
+    let mut block = mock_block();
+    block.transactions[1] = set_code_tx();
+    let batch = SingularBatch::from_block(&block).unwrap();
+    let mut span = SpanBatch::new(1_700_000_000, 10);
+    span.append(batch.clone(), 0).unwrap();
+    let encoded = span.encode().unwrap();
+
+    // The tx data column carries the authorization list after the access list
+    let fields = Rlp::new(&batch.transactions[0][1..]);
+    let mut expected = rlp::RlpStream::new_list(6);
+    for i in [6, 2, 3, 7, 8, 9] {
+        expected.append_raw(fields.at(i).unwrap().as_raw(), 1);
+    }
+    let mut expected_data = vec![SET_CODE_TX_TYPE as u8];
+    expected_data.extend_from_slice(&expected.out());
+    assert!(encoded
+        .windows(expected_data.len())
+        .any(|window| window == expected_data.as_slice()));
+    assert_eq!(span.rlp_len(), span.rlp_bytes().unwrap().len());
+}
+
+#[test]
+fn test_span_batch_rejects_unknown_transaction_types() {
+    let mut batch = SingularBatch::from_block(&mock_block()).unwrap();
+    batch.transactions[0] = vec![0x05, 0xc0].into();
+    let mut span = SpanBatch::new(1_700_000_000, 1);
+    let err = span.append(batch, 0).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<BatchError>(),
+        Some(BatchError::UnsupportedTransactionType(5))
+    ));
+    assert!(span.is_empty());
+}
+
+#[test]
+fn test_span_batch_rlp_len() {
+    let mut set_code = mock_block();
+    set_code.transactions[1] = set_code_tx();
+    let mut large = mock_block();
+    large.transactions[1].input = vec![0xab; 300].into();
+    large.transactions[1].to = Some(Address::repeat_byte(0x02));
+    let batches = [mock_block(), set_code, large]
+        .iter()
+        .map(|block| SingularBatch::from_block(block).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut span = SpanBatch::new(1_700_000_000, 1);
+    assert_eq!(span.rlp_len(), 0);
+    for i in 0..30 {
+        span.append(batches[i % batches.len()].clone(), 0).unwrap();
+        assert_eq!(span.rlp_len(), span.rlp_bytes().unwrap().len());
+    }
+    while span.len() > 1 {
+        span.pop();
+        assert_eq!(span.rlp_len(), span.rlp_bytes().unwrap().len());
+    }
+}
+
+#[test]
+fn test_span_batch_channel() {
+    let block = mock_block();
+    let mut channel = ChannelOut::new().with_span_batch(SpanBatch::new(1_700_000_000, 1));
+    channel.add_block(&block).unwrap();
+    assert_eq!(channel.ready_bytes(), 0);
+    channel.close().unwrap();
+
+    let mut decompressed = Vec::new();
+    ZlibDecoder::new(channel.buf.as_slice())
+        .read_to_end(&mut decompressed)
+        .unwrap();
+    let typed: Vec<u8> = rlp::decode(&decompressed).unwrap();
+    assert_eq!(typed, channel.span_batch.unwrap().encode().unwrap());
+}
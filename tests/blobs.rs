@@ -0,0 +1,421 @@
+use std::str::FromStr;
+
+use archon::{
+    blobs::{
+        kzg_to_versioned_hash,
+        Blob,
+        BlobSidecar,
+        BlobSidecarVersion,
+        BlobTransaction,
+        EthereumKzgCommitter,
+        BLOB_SIZE,
+        BLOB_TX_TYPE,
+        CELLS_PER_EXT_BLOB,
+        G1_POINT_AT_INFINITY,
+        GAS_PER_BLOB,
+        MAX_BLOB_DATA_SIZE,
+        TRUSTED_SETUP,
+    },
+    errors::BlobError,
+    nonce::NonceManager,
+    transactions::{
+        calldata_gas,
+        BatcherTransaction,
+        DataAvailabilityCosts,
+        DataAvailabilitySelector,
+        DataAvailabilityType,
+        TransactionConfig,
+        TransactionManager,
+        MAX_SELECTED_CHANNELS,
+        TX_GAS,
+    },
+};
+use bytes::Bytes;
+use ethers_core::{
+    types::{
+        Address,
+        U256,
+    },
+    utils::{
+        keccak256,
+        rlp::Rlp,
+    },
+};
+use ethers_middleware::SignerMiddleware;
+use ethers_providers::{
+    Http,
+    Provider,
+};
+use ethers_signers::{
+    LocalWallet,
+    Signer,
+};
+use serde_json::json;
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+mod common;
+
+use common::kzg::MockCommitter;
+
+#[test]
+fn test_blob_round_trip() {
+    for len in [0, 1, 27, 28, 127, 128, 1_000, MAX_BLOB_DATA_SIZE] {
+        let data: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+        let blob = Blob::from_data(&data).unwrap();
+        assert_eq!(blob.as_bytes().len(), BLOB_SIZE);
+        assert_eq!(blob.to_data().unwrap(), data);
+    }
+}
+
+#[test]
+fn test_blob_encoding_layout() {
+    let blob = Blob::from_data(&[0xff; 200]).unwrap();
+    let bytes = blob.as_bytes();
+    // The version and length prefix the first field element.
+    assert_eq!(&bytes[1..5], &[0, 0, 0, 200]);
+    // The first byte of every field element stays below the BLS modulus.
+    assert!(bytes
+        .chunks(32)
+        .all(|element| element[0] & 0b1100_0000 == 0));
+    assert_eq!(bytes[0], 0b0011_1111);
+    assert_eq!(bytes[32], 0b0011_1111);
+    // Only the first two rounds carry data.
+    assert!(bytes[256..].iter().all(|b| *b == 0));
+}
+
+#[test]
+fn test_blob_data_too_large() {
+    assert!(matches!(
+        Blob::from_data(&vec![1; MAX_BLOB_DATA_SIZE + 1]),
+        Err(BlobError::DataTooLarge(_))
+    ));
+}
+
+#[test]
+fn test_empty_blob_sidecar() {
+    let sidecar = BlobSidecar::empty(BlobSidecarVersion::V0);
+    assert_eq!(sidecar.blobs[0].to_data().unwrap(), Vec::<u8>::new());
+    assert_eq!(sidecar.commitments, vec![G1_POINT_AT_INFINITY]);
+    assert_eq!(sidecar.proofs, vec![G1_POINT_AT_INFINITY]);
+    let hash = kzg_to_versioned_hash(&G1_POINT_AT_INFINITY);
+    assert_eq!(hash.as_bytes()[0], 0x01);
+    assert_eq!(sidecar.versioned_hashes(), vec![hash]);
+
+    let sidecar = BlobSidecar::empty(BlobSidecarVersion::V1);
+    assert_eq!(
+        sidecar.proofs,
+        vec![G1_POINT_AT_INFINITY; CELLS_PER_EXT_BLOB]
+    );
+}
+
+#[test]
+fn test_vendored_trusted_setup() {
+    // The 4096 G1 and 65 G2 points of the EIP-4844 ceremony
+    let mut lines = TRUSTED_SETUP.lines();
+    assert_eq!(lines.next(), Some("4096"));
+    assert_eq!(lines.next(), Some("65"));
+    assert_eq!(
+        hex::encode(Sha256::digest(TRUSTED_SETUP)),
+        "d39b9f2d047cc9dca2de58f264b6a09448ccd34db967881a6713eacacf0f26b7"
+    );
+}
+
+#[test]
+fn test_ethereum_kzg_committer() {
+    let committer = EthereumKzgCommitter;
+    let zero =
+        BlobSidecar::new(BlobSidecarVersion::V0, vec![Blob::default()], &committer)
+            .unwrap();
+    assert_eq!(zero, BlobSidecar::empty(BlobSidecarVersion::V0));
+
+    let blob = Blob::from_data(b"frame data").unwrap();
+    let sidecar =
+        BlobSidecar::new(BlobSidecarVersion::V0, vec![blob.clone()], &committer).unwrap();
+    assert_ne!(sidecar.commitments[0], G1_POINT_AT_INFINITY);
+    let settings = c_kzg::KzgSettings::parse_kzg_trusted_setup(TRUSTED_SETUP, 0).unwrap();
+    assert!(settings
+        .verify_blob_kzg_proof(
+            &c_kzg::Blob::from_bytes(blob.as_bytes()).unwrap(),
+            &c_kzg::Bytes48::new(sidecar.commitments[0]),
+            &c_kzg::Bytes48::new(sidecar.proofs[0]),
+        )
+        .unwrap());
+}
+
+#[test]
+fn test_ethereum_kzg_committer_cell_proofs() {
+    let committer = EthereumKzgCommitter;
+    let zero =
+        BlobSidecar::new(BlobSidecarVersion::V1, vec![Blob::default()], &committer)
+            .unwrap();
+    assert_eq!(zero, BlobSidecar::empty(BlobSidecarVersion::V1));
+
+    let blob = Blob::from_data(b"frame data").unwrap();
+    let sidecar =
+        BlobSidecar::new(BlobSidecarVersion::V1, vec![blob.clone()], &committer).unwrap();
+    assert_eq!(sidecar.proofs.len(), CELLS_PER_EXT_BLOB);
+    let settings = c_kzg::KzgSettings::parse_kzg_trusted_setup(TRUSTED_SETUP, 0).unwrap();
+    let (cells, _) = settings
+        .compute_cells_and_kzg_proofs(&c_kzg::Blob::from_bytes(blob.as_bytes()).unwrap())
+        .unwrap();
+    let commitments =
+        vec![c_kzg::Bytes48::new(sidecar.commitments[0]); CELLS_PER_EXT_BLOB];
+    let proofs: Vec<_> = sidecar
+        .proofs
+        .iter()
+        .map(|proof| c_kzg::Bytes48::new(*proof))
+        .collect();
+    let cell_indices: Vec<u64> = (0..CELLS_PER_EXT_BLOB as u64).collect();
+    assert!(settings
+        .verify_cell_kzg_proof_batch(&commitments, &cell_indices, cells.as_ref(), &proofs)
+        .unwrap());
+}
+
+#[test]
+fn test_blob_sidecar_version_at_osaka() {
+    let osaka = archon::blobs::l1_osaka_time(1).unwrap();
+    assert_eq!(BlobSidecarVersion::at(1, osaka - 1), BlobSidecarVersion::V0);
+    assert_eq!(BlobSidecarVersion::at(1, osaka), BlobSidecarVersion::V1);
+    // Unknown chains keep the EIP-4844 sidecar
+    assert_eq!(BlobSidecarVersion::at(5, u64::MAX), BlobSidecarVersion::V0);
+}
+
+fn blob_transaction(sender: Address) -> BlobTransaction {
+    BlobTransaction {
+        chain_id: 5,
+        from: sender,
+        nonce: U256::from(3),
+        max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+        max_fee_per_gas: U256::from(41_000_000_000u64),
+        gas: U256::from(21_000),
+        to: Address::repeat_byte(0xff),
+        value: U256::zero(),
+        data: Bytes::new(),
+        max_fee_per_blob_gas: U256::from(2),
+        sidecar: BlobSidecar::new(
+            BlobSidecarVersion::V0,
+            vec![Blob::from_data(b"frame").unwrap()],
+            &MockCommitter,
+        )
+        .unwrap(),
+    }
+}
+
+#[test]
+fn test_blob_transaction_encoding() {
+    let wallet = LocalWallet::from_str(
+        "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72",
+    )
+    .unwrap();
+    let tx = blob_transaction(wallet.address());
+    let signature = wallet.sign_hash(tx.sighash());
+    assert_eq!(signature.recover(tx.sighash()).unwrap(), wallet.address());
+
+    let encoded = tx.rlp_signed(&signature);
+    assert_eq!(encoded[0], BLOB_TX_TYPE);
+    let rlp = Rlp::new(&encoded[1..]);
+    assert_eq!(rlp.item_count().unwrap(), 4);
+
+    // The transaction hash covers the signed payload without the sidecar.
+    let payload = rlp.at(0).unwrap();
+    assert_eq!(payload.item_count().unwrap(), 14);
+    let mut hashed = vec![BLOB_TX_TYPE];
+    hashed.extend_from_slice(payload.as_raw());
+    assert_eq!(tx.hash(&signature).as_bytes(), keccak256(hashed));
+
+    let versioned_hashes: Vec<Vec<u8>> = payload.list_at(10).unwrap();
+    assert_eq!(versioned_hashes.len(), 1);
+    assert_eq!(
+        versioned_hashes[0],
+        kzg_to_versioned_hash(&tx.sidecar.commitments[0]).as_bytes()
+    );
+    let blobs: Vec<Vec<u8>> = rlp.list_at(1).unwrap();
+    assert_eq!(blobs[0], tx.sidecar.blobs[0].as_bytes());
+    let commitments: Vec<Vec<u8>> = rlp.list_at(2).unwrap();
+    assert_eq!(commitments[0], tx.sidecar.commitments[0]);
+    let proofs: Vec<Vec<u8>> = rlp.list_at(3).unwrap();
+    assert_eq!(proofs[0], tx.sidecar.proofs[0]);
+}
+
+#[test]
+fn test_blob_transaction_cell_proofs_encoding() {
+    let wallet = LocalWallet::from_str(
+        "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72",
+    )
+    .unwrap();
+    let mut tx = blob_transaction(wallet.address());
+    tx.sidecar =
+        BlobSidecar::new(BlobSidecarVersion::V1, tx.sidecar.blobs, &MockCommitter)
+            .unwrap();
+    let signature = wallet.sign_hash(tx.sighash());
+
+    let encoded = tx.rlp_signed(&signature);
+    assert_eq!(encoded[0], BLOB_TX_TYPE);
+    let rlp = Rlp::new(&encoded[1..]);
+    assert_eq!(rlp.item_count().unwrap(), 5);
+    assert_eq!(rlp.at(0).unwrap().item_count().unwrap(), 14);
+    assert_eq!(rlp.val_at::<u8>(1).unwrap(), 1);
+    let blobs: Vec<Vec<u8>> = rlp.list_at(2).unwrap();
+    assert_eq!(blobs[0], tx.sidecar.blobs[0].as_bytes());
+    let commitments: Vec<Vec<u8>> = rlp.list_at(3).unwrap();
+    assert_eq!(commitments[0], tx.sidecar.commitments[0]);
+    let proofs: Vec<Vec<u8>> = rlp.list_at(4).unwrap();
+    assert_eq!(proofs.len(), CELLS_PER_EXT_BLOB);
+    assert_eq!(
+        proofs[CELLS_PER_EXT_BLOB - 1],
+        tx.sidecar.proofs[CELLS_PER_EXT_BLOB - 1]
+    );
+}
+
+#[test]
+fn test_blob_transaction_cancellation() {
+    let tx = BatcherTransaction::Blob(blob_transaction(Address::repeat_byte(0x11)));
+    let BatcherTransaction::Blob(cancellation) =
+        TransactionManager::cancellation_transaction(&tx).unwrap()
+    else {
+        panic!("blob transactions must be cancelled by blob transactions");
+    };
+    assert_eq!(cancellation.to, Address::repeat_byte(0x11));
+    assert_eq!(cancellation.nonce, U256::from(3));
+    assert_eq!(
+        cancellation.sidecar,
+        BlobSidecar::empty(BlobSidecarVersion::V0)
+    );
+}
+
+#[test]
+fn test_data_availability_type_from_str() {
+    assert_eq!(
+        DataAvailabilityType::from_str("blobs").unwrap(),
+        DataAvailabilityType::Blobs
+    );
+    assert_eq!(
+        DataAvailabilityType::from_str("Calldata").unwrap(),
+        DataAvailabilityType::Calldata
+    );
+    assert_eq!(
+        DataAvailabilityType::from_str("auto").unwrap(),
+        DataAvailabilityType::Auto
+    );
+    assert!(DataAvailabilityType::from_str("celestia").is_err());
+}
+
+#[test]
+fn test_estimate_data_availability_costs() {
+    let data = vec![0xff; MAX_BLOB_DATA_SIZE];
+    let gwei = U256::from(1_000_000_000u64);
+    let costs = DataAvailabilityCosts::estimate(&data, gwei * 10, gwei, U256::one());
+    assert_eq!(costs.calldata, gwei * 11 * calldata_gas(&data));
+    assert_eq!(costs.blobs, gwei * 11 * TX_GAS + GAS_PER_BLOB);
+    assert_eq!(costs.cheapest(), DataAvailabilityType::Blobs);
+
+    // Each frame is posted in a single blob, so larger frames need calldata
+    let data = vec![0xff; MAX_BLOB_DATA_SIZE + 1];
+    let costs = DataAvailabilityCosts::estimate(&data, gwei * 10, gwei, U256::one());
+    assert_eq!(costs.blobs, U256::MAX);
+    assert_eq!(costs.cheapest(), DataAvailabilityType::Calldata);
+
+    // Expensive blob gas makes calldata cheaper
+    let costs =
+        DataAvailabilityCosts::estimate(&[0xff; 100], gwei, U256::zero(), gwei * 100);
+    assert_eq!(costs.cheapest(), DataAvailabilityType::Calldata);
+
+    // Calldata wins ties
+    let tie = DataAvailabilityCosts {
+        calldata: U256::one(),
+        blobs: U256::one(),
+    };
+    assert_eq!(tie.cheapest(), DataAvailabilityType::Calldata);
+}
+
+#[test]
+fn test_data_availability_selector() {
+    let blobs_cheaper = DataAvailabilityCosts {
+        calldata: U256::from(2),
+        blobs: U256::one(),
+    };
+    let calldata_cheaper = DataAvailabilityCosts {
+        calldata: U256::one(),
+        blobs: U256::from(2),
+    };
+    let mut selector = DataAvailabilitySelector::new();
+    assert_eq!(selector.channel("a"), None);
+    assert_eq!(
+        selector.select("a", &blobs_cheaper),
+        DataAvailabilityType::Blobs
+    );
+    assert_eq!(
+        selector.select("b", &calldata_cheaper),
+        DataAvailabilityType::Calldata
+    );
+
+    // Channels keep the data availability selected for their first frame
+    assert_eq!(selector.channel("a"), Some(DataAvailabilityType::Blobs));
+    assert_eq!(selector.channel("b"), Some(DataAvailabilityType::Calldata));
+
+    // Only the most recent channels are remembered
+    for i in 0..MAX_SELECTED_CHANNELS - 1 {
+        selector.select(&i.to_string(), &calldata_cheaper);
+    }
+    assert_eq!(selector.channel("a"), None);
+    assert_eq!(selector.channel("b"), Some(DataAvailabilityType::Calldata));
+}
+
+#[tokio::test]
+async fn test_publish_blob_transaction_to_devnet() {
+    let devnet = common::devnet::Devnet::default()
+        .with_result(
+            "eth_getBlockByNumber",
+            json!({ "number": "0x10", "baseFeePerGas": "0x9502f900" }),
+        )
+        .with_result("eth_maxPriorityFeePerGas", json!("0x3b9aca00"))
+        .with_result("eth_blobBaseFee", json!("0x3"))
+        .with_result("eth_getTransactionCount", json!("0x7"))
+        .with_result(
+            "eth_sendRawTransaction",
+            json!(format!("{:?}", ethers_core::types::H256::repeat_byte(0xaa))),
+        );
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let wallet = LocalWallet::from_str(
+        "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72",
+    )
+    .unwrap()
+    .with_chain_id(5u64);
+    let inbox = Address::repeat_byte(0xff);
+    let config = TransactionConfig {
+        data_availability_type: DataAvailabilityType::Blobs,
+        ..Default::default()
+    };
+
+    let tx = TransactionManager::craft_blob_transaction(
+        5,
+        inbox,
+        wallet.address(),
+        &provider,
+        &config,
+        &NonceManager::new(),
+        &MockCommitter,
+        Bytes::from_static(b"frame data"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(tx.nonce, U256::from(7));
+    assert_eq!(tx.max_fee_per_gas, U256::from(6_000_000_000u64));
+    assert_eq!(tx.max_fee_per_blob_gas, U256::from(6));
+    assert_eq!(tx.sidecar.blobs[0].to_data().unwrap(), b"frame data");
+
+    let client = SignerMiddleware::new(provider, wallet.clone());
+    let signature = wallet.sign_hash(tx.sighash());
+    let expected = tx.rlp_signed(&signature);
+    let tx_hash = TransactionManager::publish(&client, &BatcherTransaction::Blob(tx))
+        .await
+        .unwrap();
+    assert_eq!(tx_hash, ethers_core::types::H256::repeat_byte(0xaa));
+
+    let calls = devnet.calls("eth_sendRawTransaction");
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0][0], json!(format!("0x{}", hex::encode(expected))));
+}
@@ -0,0 +1,582 @@
+use archon::{
+    builder::{
+        ChannelConfig,
+        Frame,
+        DERIVATION_VERSION_0,
+    },
+    channels::{
+        ChannelManager,
+        TransactionID,
+        MAX_UNSAFE_BLOCKS_PER_CHUNK,
+    },
+    errors::ChannelManagerError,
+    nonce::NonceManager,
+    rollup::{
+        BlockNumHash,
+        L2BlockRef,
+        SyncStatus,
+        CHANNEL_TIMEOUT_GRANITE,
+    },
+    state::State,
+    test_utils::mock_chain,
+    transactions::Cancellation,
+};
+use ethers_core::types::{
+    Block,
+    BlockId,
+    BlockNumber,
+    Transaction,
+    H256,
+    U64,
+};
+use ethers_providers::{
+    Http,
+    Provider,
+};
+use serde_json::{
+    json,
+    Value,
+};
+use std::sync::Mutex;
+
+mod common;
+
+fn l1_block_id() -> BlockId {
+    l1_block_at(100)
+}
+
+fn l1_block_at(number: u64) -> BlockId {
+    BlockId::Number(BlockNumber::Number(U64::from(number)))
+}
+
+fn channel_manager(target_frame_size: usize) -> ChannelManager {
+    let mut cm = ChannelManager::new();
+    cm.with_channel_config(ChannelConfig {
+        max_frame_size: 1_000,
+        target_frame_size,
+        channel_timeout: 10,
+        ..ChannelConfig::default()
+    });
+    cm
+}
+
+#[test]
+fn test_tx_data_without_blocks() {
+    let mut cm = channel_manager(1_000);
+    assert!(cm.tx_data(l1_block_id()).is_err());
+}
+
+#[test]
+fn test_tx_data_outputs_full_channel() {
+    let mut cm = channel_manager(1_000);
+    for block in mock_chain(1, 7, 500) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+
+    let mut frames = Vec::new();
+    while let Ok((data, id)) = cm.tx_data(l1_block_id()) {
+        assert_eq!(data[0], DERIVATION_VERSION_0);
+        assert!(data.len() <= 1_001);
+        let (frame, consumed) = Frame::decode(&data[1..]).unwrap();
+        assert_eq!(consumed, data.len() - 1);
+        assert_eq!(id, TransactionID::new(frame.id, frame.number as u64));
+        frames.push(frame);
+    }
+
+    // Blocks of ~500 random bytes fill a 1000 byte target after 5 blocks at a 0.4 ratio,
+    // so one channel is closed and the remaining blocks stay in the open channel.
+    assert!(frames.len() > 1);
+    assert!(frames.iter().all(|f| f.id == frames[0].id));
+    assert!(frames.last().unwrap().is_last);
+    assert_eq!(
+        frames.iter().map(|f| f.number).collect::<Vec<_>>(),
+        (0..frames.len() as u16).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_tx_data_does_not_output_open_channel() {
+    let mut cm = channel_manager(100_000);
+    for block in mock_chain(1, 3, 100) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+    assert!(cm.tx_data(l1_block_id()).is_err());
+}
+
+/// Fills the [ChannelManager] with one closed channel and returns the ids of its frames.
+fn submit_closed_channel(cm: &mut ChannelManager) -> Vec<TransactionID> {
+    for block in mock_chain(1, 5, 500) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+    let mut ids = Vec::new();
+    while let Ok((_, id)) = cm.tx_data(l1_block_id()) {
+        ids.push(id);
+    }
+    assert!(!ids.is_empty());
+    ids
+}
+
+#[test]
+fn test_tx_failed_requeues_frame() {
+    let mut cm = channel_manager(1_000);
+    for block in mock_chain(1, 5, 500) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+    let (data, id) = cm.tx_data(l1_block_id()).unwrap();
+    cm.tx_failed(id.clone());
+    assert_eq!(cm.tx_data(l1_block_id()).unwrap(), (data, id));
+}
+
+#[test]
+fn test_tx_confirmed_drops_fully_confirmed_channel() {
+    let mut cm = channel_manager(1_000);
+    let ids = submit_closed_channel(&mut cm);
+    assert_eq!(cm.channels().len(), 1);
+
+    let (last, rest) = ids.split_last().unwrap();
+    for id in rest {
+        cm.tx_confirmed(id.clone(), l1_block_id());
+    }
+    assert_eq!(cm.channels().len(), 1);
+    cm.tx_confirmed(last.clone(), l1_block_id());
+    assert!(cm.channels().is_empty());
+}
+
+#[test]
+fn test_tx_failed_keeps_channel_until_resubmitted() {
+    let mut cm = channel_manager(1_000);
+    let ids = submit_closed_channel(&mut cm);
+    let (last, rest) = ids.split_last().unwrap();
+    for id in rest {
+        cm.tx_confirmed(id.clone(), l1_block_id());
+    }
+    cm.tx_failed(last.clone());
+    assert_eq!(cm.channels().len(), 1);
+
+    let (_, id) = cm.tx_data(l1_block_id()).unwrap();
+    assert_eq!(&id, last);
+    cm.tx_confirmed(id, l1_block_id());
+    assert!(cm.channels().is_empty());
+}
+
+#[test]
+fn test_tx_failed_with_later_frames_pending_rebuilds_channel() {
+    let mut cm = channel_manager(1_000);
+    for block in mock_chain(1, 5, 500) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+    let (_, first) = cm.tx_data(l1_block_id()).unwrap();
+    let (_, second) = cm.tx_data(l1_block_id()).unwrap();
+    assert_eq!(first.frame_number(), 0);
+    assert_eq!(second.frame_number(), 1);
+
+    // Frame 0 fails while frame 1 is pending, so resubmitting it would reorder the frames
+    cm.tx_failed(first.clone());
+    let (_, id) = cm.tx_data(l1_block_id()).unwrap();
+    assert_ne!(id.channel_id(), first.channel_id());
+    assert_eq!(id.frame_number(), 0);
+    assert_eq!(cm.channels().len(), 1);
+    assert_eq!(cm.channels()[0].blocks().len(), 5);
+
+    // Outcomes of the stale frame 1 are ignored
+    cm.tx_confirmed(second, l1_block_id());
+    assert_eq!(cm.channels()[0].first_inclusion_block(), None);
+}
+
+#[test]
+fn test_tx_failed_with_newer_channel_pending_rebuilds_channels() {
+    let mut cm = channel_manager(1_000);
+    for block in mock_chain(1, 10, 500) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+    let mut ids = Vec::new();
+    while let Ok((_, id)) = cm.tx_data(l1_block_id()) {
+        ids.push(id);
+    }
+    let newer = ids
+        .iter()
+        .position(|id| id.channel_id() != ids[0].channel_id())
+        .unwrap();
+    assert_eq!(cm.channels().len(), 2);
+
+    // The last frame of the first channel would take a nonce after the newer channel's frames
+    cm.tx_failed(ids[newer - 1].clone());
+    assert!(cm.channels().is_empty());
+    let (_, id) = cm.tx_data(l1_block_id()).unwrap();
+    assert_eq!(id.frame_number(), 0);
+    assert!(ids.iter().all(|old| old.channel_id() != id.channel_id()));
+    assert_eq!(cm.channels().len(), 2);
+    assert_eq!(cm.channels()[0].blocks()[0].number, Some(U64::from(1)));
+}
+
+#[test]
+fn test_channel_timeout_on_l1_head() {
+    let mut cm = channel_manager(1_000);
+    let ids = submit_closed_channel(&mut cm);
+    cm.tx_confirmed(ids[0].clone(), l1_block_at(100));
+    assert_eq!(cm.channels()[0].first_inclusion_block(), Some(100));
+
+    // The channel is still within its timeout.
+    assert!(cm.tx_data(l1_block_at(109)).is_err());
+    assert_eq!(cm.channels().len(), 1);
+
+    // Once timed out, its blocks are re-batched into a fresh channel.
+    let (_, id) = cm.tx_data(l1_block_at(110)).unwrap();
+    assert_ne!(id, ids[0]);
+    assert_eq!(cm.channels().len(), 1);
+    assert_eq!(cm.channels()[0].blocks().len(), 5);
+    assert_eq!(cm.channels()[0].first_inclusion_block(), None);
+}
+
+#[test]
+fn test_channel_timeout_follows_granite() {
+    // The channel starts with an L2 block at Granite, so the Granite timeout applies
+    let mut cm = ChannelManager::new();
+    cm.with_channel_config(ChannelConfig {
+        max_frame_size: 1_000,
+        target_frame_size: 1_000,
+        channel_timeout: 10,
+        granite_time: Some(1_700_000_002),
+        ..ChannelConfig::default()
+    });
+    let ids = submit_closed_channel(&mut cm);
+    cm.tx_confirmed(ids[0].clone(), l1_block_at(100));
+    assert!(cm.tx_data(l1_block_at(110)).is_err());
+    assert!(cm.tx_data(l1_block_at(149)).is_err());
+    assert_eq!(cm.channels().len(), 1);
+
+    let (_, id) = cm.tx_data(l1_block_at(150)).unwrap();
+    assert!(!ids.contains(&id));
+    assert_eq!(cm.channels()[0].blocks().len(), 5);
+}
+
+#[test]
+fn test_channel_timeout_at_granite_time() {
+    let config = ChannelConfig {
+        channel_timeout: 300,
+        granite_time: Some(1_000),
+        ..ChannelConfig::default()
+    };
+    assert_eq!(config.channel_timeout_at(999), 300);
+    assert_eq!(config.channel_timeout_at(1_000), CHANNEL_TIMEOUT_GRANITE);
+    let config = ChannelConfig {
+        granite_time: None,
+        ..config
+    };
+    assert_eq!(config.channel_timeout_at(1_000), 300);
+}
+
+#[test]
+fn test_channel_timeout_on_late_confirmation() {
+    let mut cm = channel_manager(1_000);
+    let ids = submit_closed_channel(&mut cm);
+    let (last, rest) = ids.split_last().unwrap();
+    for id in rest {
+        cm.tx_confirmed(id.clone(), l1_block_at(100));
+    }
+    cm.tx_confirmed(last.clone(), l1_block_at(111));
+    assert!(cm.channels().is_empty());
+
+    let (_, id) = cm.tx_data(l1_block_at(111)).unwrap();
+    assert!(!ids.contains(&id));
+    assert_eq!(cm.channels()[0].blocks().len(), 5);
+}
+
+#[test]
+fn test_max_channel_duration_closes_channel() {
+    let mut cm = ChannelManager::new();
+    cm.with_channel_config(ChannelConfig {
+        max_frame_size: 1_000,
+        max_channel_duration: 5,
+        ..ChannelConfig::default()
+    });
+    for block in mock_chain(1, 2, 100) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+    assert!(cm.tx_data(l1_block_at(100)).is_err());
+    assert_eq!(cm.channels()[0].duration_deadline(), Some(105));
+    assert!(cm.tx_data(l1_block_at(104)).is_err());
+
+    let (data, _) = cm.tx_data(l1_block_at(105)).unwrap();
+    let (frame, _) = Frame::decode(&data[1..]).unwrap();
+    assert!(frame.is_last);
+    assert!(cm.channels()[0].is_closed());
+}
+
+#[test]
+fn test_max_channel_duration_disabled() {
+    let mut cm = channel_manager(100_000);
+    for block in mock_chain(1, 2, 100) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+    assert!(cm.tx_data(l1_block_at(100)).is_err());
+    assert_eq!(cm.channels()[0].duration_deadline(), None);
+    assert!(cm.tx_data(l1_block_at(1_000)).is_err());
+}
+
+#[test]
+fn test_store_block_detects_l2_reorg() {
+    let cm = channel_manager(1_000);
+    let state = cm.state();
+    let mut chain = mock_chain(1, 3, 10);
+    assert_eq!(
+        ChannelManager::store_block(&state, chain[0].clone()).unwrap(),
+        1
+    );
+    chain[1].parent_hash = Default::default();
+    let err = ChannelManager::store_block(&state, chain[1].clone()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ChannelManagerError>(),
+        Some(ChannelManagerError::L2Reorg)
+    ));
+}
+
+#[test]
+fn test_state_reset_discards_pending_channels() {
+    let cancellation = Cancellation::new();
+    let mut cm = channel_manager(1_000);
+    cm.with_cancellation(cancellation.clone());
+    let ids = submit_closed_channel(&mut cm);
+    assert_eq!(cm.channels().len(), 1);
+    let in_flight = cancellation.track();
+    assert!(!in_flight.is_cancelled());
+
+    cm.state().lock().unwrap().reset();
+    for block in mock_chain(1, 5, 500) {
+        cm.state().lock().unwrap().add_block(block);
+    }
+    let (_, id) = cm.tx_data(l1_block_id()).unwrap();
+    assert!(!ids.contains(&id));
+    assert_eq!(cm.channels().len(), 1);
+    assert!(in_flight.is_cancelled());
+
+    // Outcomes of frames from discarded channels are ignored.
+    cm.tx_confirmed(ids[0].clone(), l1_block_id());
+    assert_eq!(cm.channels().len(), 1);
+}
+
+#[test]
+fn test_l1_reorg_invalidates_confirmed_channel() {
+    let mut cm = channel_manager(1_000);
+    let ids = submit_closed_channel(&mut cm);
+    cm.tx_confirmed(ids[0].clone(), l1_block_at(100));
+    cm.tx_confirmed(ids[1].clone(), l1_block_at(102));
+
+    // Confirmations at or below the common ancestor are kept.
+    cm.l1_reorg(102);
+    assert_eq!(cm.channels().len(), 1);
+
+    cm.l1_reorg(101);
+    assert!(cm.channels().is_empty());
+    let (_, id) = cm.tx_data(l1_block_at(102)).unwrap();
+    assert!(!ids.contains(&id));
+    assert_eq!(cm.channels()[0].blocks().len(), 5);
+}
+
+#[test]
+fn test_l1_reorg_resets_nonce() {
+    let nonce_manager = NonceManager::new();
+    nonce_manager.sync(7).unwrap();
+    let mut cm = channel_manager(1_000);
+    cm.with_nonce_manager(nonce_manager.clone());
+
+    cm.l1_reorg(100);
+    assert!(!nonce_manager.is_synced());
+}
+
+fn sync_status(safe_l2: u64, unsafe_l2: u64) -> SyncStatus {
+    let block_ref = |number| L2BlockRef {
+        hash: H256::from_low_u64_be(number),
+        number,
+        ..Default::default()
+    };
+    SyncStatus {
+        safe_l2: block_ref(safe_l2),
+        unsafe_l2: block_ref(unsafe_l2),
+        ..Default::default()
+    }
+}
+
+fn l2_node(blocks: &[Block<Transaction>]) -> common::devnet::Devnet {
+    blocks
+        .iter()
+        .fold(common::devnet::Devnet::default(), |devnet, block| {
+            devnet.with_result_for(
+                "eth_getBlockByNumber",
+                json!([format!("{:#x}", block.number.unwrap()), true]),
+                serde_json::to_value(block).unwrap(),
+            )
+        })
+}
+
+fn block_at(number: u64) -> BlockNumHash {
+    BlockNumHash {
+        hash: H256::from_low_u64_be(number),
+        number,
+    }
+}
+
+#[tokio::test]
+async fn test_load_unsafe_blocks() {
+    let devnet = l2_node(&mock_chain(1, 5, 10));
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let state = Mutex::new(State::new());
+
+    // Exactly the blocks between the safe and unsafe heads are loaded
+    let last =
+        ChannelManager::load_unsafe_blocks(&provider, &state, &sync_status(2, 4), None)
+            .await;
+    assert_eq!(last, Some(block_at(4)));
+    let numbers = state
+        .lock()
+        .unwrap()
+        .take_blocks()
+        .iter()
+        .map(|block| block.number.unwrap().as_u64())
+        .collect::<Vec<_>>();
+    assert_eq!(numbers, vec![3, 4]);
+    // Blocks are fetched forward by number from the safe head
+    let calls = devnet.calls("eth_getBlockByNumber");
+    assert_eq!(calls, vec![json!(["0x3", true]), json!(["0x4", true])]);
+
+    // Loading resumes after the last loaded block
+    let last =
+        ChannelManager::load_unsafe_blocks(&provider, &state, &sync_status(2, 5), last)
+            .await;
+    assert_eq!(last, Some(block_at(5)));
+    assert_eq!(state.lock().unwrap().take_blocks().len(), 1);
+    assert_eq!(devnet.calls("eth_getBlockByNumber").len(), 3);
+
+    // Nothing is fetched while the unsafe head stays put
+    let last =
+        ChannelManager::load_unsafe_blocks(&provider, &state, &sync_status(2, 5), last)
+            .await;
+    assert_eq!(last, Some(block_at(5)));
+    assert_eq!(devnet.calls("eth_getBlockByNumber").len(), 3);
+}
+
+#[tokio::test]
+async fn test_load_unsafe_blocks_stops_at_fetch_failure() {
+    let mut chain = mock_chain(1, 5, 10);
+    chain.remove(3);
+    let devnet = l2_node(&chain);
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let state = Mutex::new(State::new());
+
+    // Block 4 is missing, so its chunk is not loaded until the next poll
+    let last =
+        ChannelManager::load_unsafe_blocks(&provider, &state, &sync_status(1, 5), None)
+            .await;
+    assert_eq!(last, None);
+    assert!(state.lock().unwrap().take_blocks().is_empty());
+    assert!(!state.lock().unwrap().take_reset());
+    assert_eq!(devnet.calls("eth_getBlockByNumber").len(), 3);
+}
+
+#[tokio::test]
+async fn test_load_unsafe_blocks_keeps_loaded_chunks_across_polls() {
+    let chunk = MAX_UNSAFE_BLOCKS_PER_CHUNK;
+    let chain = mock_chain(1, 2 * chunk + 1, 10);
+    let missing = chain[chunk as usize + 5].clone();
+    let devnet = l2_node(&chain);
+    let _ = devnet.clone().with_result_for(
+        "eth_getBlockByNumber",
+        json!([format!("{:#x}", missing.number.unwrap()), true]),
+        Value::Null,
+    );
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let state = Mutex::new(State::new());
+
+    // A block of the second chunk is missing, so only the first chunk is loaded
+    let status = sync_status(1, 2 * chunk + 1);
+    let last = ChannelManager::load_unsafe_blocks(&provider, &state, &status, None).await;
+    assert_eq!(last, Some(block_at(chunk + 1)));
+    assert_eq!(state.lock().unwrap().take_blocks().len(), chunk as usize);
+
+    // Once the block is available, loading resumes after the first chunk
+    let _ = devnet.clone().with_result_for(
+        "eth_getBlockByNumber",
+        json!([format!("{:#x}", missing.number.unwrap()), true]),
+        serde_json::to_value(&missing).unwrap(),
+    );
+    let fetched = devnet.calls("eth_getBlockByNumber").len();
+    let last = ChannelManager::load_unsafe_blocks(&provider, &state, &status, last).await;
+    assert_eq!(last, Some(block_at(2 * chunk + 1)));
+    assert_eq!(state.lock().unwrap().take_blocks().len(), chunk as usize);
+    assert_eq!(
+        devnet.calls("eth_getBlockByNumber")[fetched],
+        json!([format!("{:#x}", chunk + 2), true])
+    );
+    assert!(!state.lock().unwrap().take_reset());
+}
+
+#[tokio::test]
+async fn test_load_unsafe_blocks_checks_rollup_node_heads() {
+    let devnet = l2_node(&mock_chain(1, 5, 10));
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let state = Mutex::new(State::new());
+
+    // The unsafe head of the L2 node differs from the rollup node's
+    let mut status = sync_status(1, 3);
+    status.unsafe_l2.hash = H256::repeat_byte(0xff);
+    let last = ChannelManager::load_unsafe_blocks(&provider, &state, &status, None).await;
+    assert_eq!(last, None);
+    assert!(state.lock().unwrap().take_blocks().is_empty());
+    assert!(!state.lock().unwrap().take_reset());
+
+    // The safe head of the L2 node differs from the rollup node's
+    let mut status = sync_status(3, 5);
+    status.safe_l2.hash = H256::repeat_byte(0xff);
+    let last = ChannelManager::load_unsafe_blocks(&provider, &state, &status, None).await;
+    assert_eq!(last, None);
+    assert!(state.lock().unwrap().take_blocks().is_empty());
+    assert!(!state.lock().unwrap().take_reset());
+}
+
+#[tokio::test]
+async fn test_load_unsafe_blocks_resets_on_l2_reorg() {
+    let mut chain = mock_chain(1, 5, 10);
+    chain[3].parent_hash = H256::repeat_byte(0xff);
+    let devnet = l2_node(&chain);
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let state = Mutex::new(State::new());
+    let last =
+        ChannelManager::load_unsafe_blocks(&provider, &state, &sync_status(1, 3), None)
+            .await;
+    assert_eq!(last, Some(block_at(3)));
+
+    // Block 4 no longer extends the loaded block 3
+    let last =
+        ChannelManager::load_unsafe_blocks(&provider, &state, &sync_status(1, 5), last)
+            .await;
+    assert_eq!(last, None);
+    assert!(state.lock().unwrap().take_blocks().is_empty());
+    assert!(state.lock().unwrap().take_reset());
+}
+
+#[tokio::test]
+async fn test_load_unsafe_blocks_prunes_when_safe_head_advances() {
+    let devnet = l2_node(&mock_chain(1, 7, 10));
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let state = Mutex::new(State::new());
+    let last =
+        ChannelManager::load_unsafe_blocks(&provider, &state, &sync_status(1, 3), None)
+            .await;
+    assert_eq!(last, Some(block_at(3)));
+
+    // The safe head moved past the loaded blocks, so they are pruned
+    // and loading resumes from the safe head without a reset
+    let last =
+        ChannelManager::load_unsafe_blocks(&provider, &state, &sync_status(5, 7), last)
+            .await;
+    assert_eq!(last, Some(block_at(7)));
+    let numbers = state
+        .lock()
+        .unwrap()
+        .take_blocks()
+        .iter()
+        .map(|block| block.number.unwrap().as_u64())
+        .collect::<Vec<_>>();
+    assert_eq!(numbers, vec![6, 7]);
+    assert!(!state.lock().unwrap().take_reset());
+}
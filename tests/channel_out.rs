@@ -0,0 +1,118 @@
+use std::io::Read;
+
+use archon::builder::{
+    ChannelOut,
+    Frame,
+    DERIVATION_VERSION_0,
+    FRAME_OVERHEAD,
+    MAX_RLP_BYTES_PER_CHANNEL,
+};
+use flate2::read::ZlibDecoder;
+
+#[test]
+fn test_frame_round_trip() {
+    let frame = Frame {
+        id: [0xab; 16],
+        number: 7,
+        data: vec![1, 2, 3, 4],
+        is_last: true,
+    };
+    let encoded = frame.encode();
+    assert_eq!(encoded.len(), FRAME_OVERHEAD + 4);
+    assert_eq!(&encoded[16..18], &[0, 7]);
+    assert_eq!(&encoded[18..22], &[0, 0, 0, 4]);
+    assert_eq!(encoded[encoded.len() - 1], 1);
+
+    let (decoded, consumed) = Frame::decode(&encoded).unwrap();
+    assert_eq!(consumed, encoded.len());
+    assert_eq!(decoded, frame);
+}
+
+#[test]
+fn test_output_frames_respect_max_size() {
+    let mut channel = ChannelOut::new();
+    channel.buf = vec![0x42; 100];
+    channel.closed = true;
+
+    let max_size = FRAME_OVERHEAD + 40;
+    let mut frames = Vec::new();
+    loop {
+        let (frame, tx_data) = channel.output_tx_data(max_size).unwrap().unwrap();
+        assert_eq!(tx_data[0], DERIVATION_VERSION_0);
+        assert!(tx_data.len() <= max_size + 1);
+        let is_last = frame.is_last;
+        frames.push(frame);
+        if is_last {
+            break
+        }
+    }
+
+    assert_eq!(frames.len(), 3);
+    assert_eq!(
+        frames.iter().map(|f| f.number).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    assert_eq!(frames.iter().map(|f| f.data.len()).sum::<usize>(), 100);
+    assert!(frames.iter().all(|f| f.id == channel.id));
+}
+
+#[test]
+fn test_output_frame_rejects_small_max_size() {
+    let mut channel = ChannelOut::new();
+    assert!(channel.output_frame(FRAME_OVERHEAD).is_err());
+}
+
+#[test]
+fn test_output_frame_waits_for_data() {
+    let mut channel = ChannelOut::new();
+    assert!(channel.output_frame(FRAME_OVERHEAD + 64).unwrap().is_none());
+    assert_eq!(channel.frame, 0);
+
+    // Closing buffers the end of the compressed stream, which makes up the last frame
+    channel.close().unwrap();
+    let frame = channel.output_frame(FRAME_OVERHEAD + 64).unwrap().unwrap();
+    assert_eq!(frame.number, 0);
+    assert!(frame.is_last);
+    assert!(!frame.data.is_empty());
+    assert!(channel.output_frame(FRAME_OVERHEAD + 64).unwrap().is_none());
+}
+
+#[test]
+fn test_compressed_channel_round_trip() {
+    let batches: Vec<Vec<u8>> = (0..10u8).map(|i| vec![i; 500]).collect();
+    let mut channel = ChannelOut::new();
+    for batch in &batches {
+        channel.add_batch(batch).unwrap();
+    }
+    assert_eq!(channel.input_bytes(), 5000);
+    channel.flush().unwrap();
+    assert!(channel.ready_bytes() > 0);
+    channel.close().unwrap();
+    assert!(channel.add_batch(&[0x01]).is_err());
+
+    let mut compressed = Vec::new();
+    loop {
+        let frame = channel.output_frame(FRAME_OVERHEAD + 64).unwrap().unwrap();
+        compressed.extend(frame.data);
+        if frame.is_last {
+            break
+        }
+    }
+    assert_eq!(channel.ready_bytes(), 0);
+
+    let mut decompressed = Vec::new();
+    ZlibDecoder::new(compressed.as_slice())
+        .read_to_end(&mut decompressed)
+        .unwrap();
+    assert_eq!(decompressed, batches.concat());
+}
+
+#[test]
+fn test_add_batch_rejects_too_many_rlp_bytes() {
+    let mut channel = ChannelOut::new();
+    channel
+        .add_batch(&vec![0u8; MAX_RLP_BYTES_PER_CHANNEL - 1])
+        .unwrap();
+    assert!(channel.add_batch(&[0u8; 2]).is_err());
+    assert_eq!(channel.input_bytes(), MAX_RLP_BYTES_PER_CHANNEL - 1);
+}
@@ -0,0 +1,111 @@
+use ethers_core::utils::keccak256;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+use tokio::{
+    io::{
+        AsyncBufReadExt,
+        AsyncReadExt,
+        AsyncWriteExt,
+        BufReader,
+    },
+    net::{
+        TcpListener,
+        TcpStream,
+    },
+};
+
+/// The commitment type byte of keccak256 commitments.
+pub const KECCAK_COMMITMENT_TYPE: u8 = 0x00;
+
+/// A local stand-in for an alt-DA server, committing to data by its keccak256 hash.
+#[derive(Debug, Clone, Default)]
+pub struct DaServer {
+    /// The stored data by commitment
+    inputs: Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>>,
+    /// The HTTP status to answer with instead of storing the data, if any
+    failure: Arc<Mutex<Option<u16>>>,
+}
+
+impl DaServer {
+    /// Answers every request with the given HTTP status instead of storing the data.
+    pub fn failing_with(self, status: u16) -> Self {
+        *self.failure.lock().unwrap() = Some(status);
+        self
+    }
+
+    /// Returns the commitment the server answers for the data.
+    pub fn commitment(data: &[u8]) -> Vec<u8> {
+        let mut commitment = vec![KECCAK_COMMITMENT_TYPE];
+        commitment.extend_from_slice(&keccak256(data));
+        commitment
+    }
+
+    /// Returns the data stored under the commitment, if any.
+    pub fn input(&self, commitment: &[u8]) -> Option<Vec<u8>> {
+        self.inputs.lock().unwrap().get(commitment).cloned()
+    }
+
+    /// Serves the alt-DA API over HTTP on a local port, returning its url.
+    pub async fn serve(&self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = self.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let server = server.clone();
+                tokio::spawn(async move { server.handle_connection(stream).await });
+            }
+        });
+        url
+    }
+
+    /// Answers the HTTP requests of a keep-alive connection.
+    async fn handle_connection(&self, stream: TcpStream) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        while reader.read_line(&mut request_line).await? > 0 {
+            let mut content_length = 0;
+            let mut line = String::new();
+            loop {
+                line.clear();
+                reader.read_line(&mut line).await?;
+                let Some((name, value)) = line.trim_end().split_once(':') else {
+                    break
+                };
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or_default();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).await?;
+            let (status, body) = self.respond(request_line.trim_end(), body);
+            let mut response = format!(
+                "HTTP/1.1 {status} OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\n\r\n",
+                body.len()
+            )
+            .into_bytes();
+            response.extend_from_slice(&body);
+            reader.get_mut().write_all(&response).await?;
+            request_line.clear();
+        }
+        Ok(())
+    }
+
+    /// Stores the data of a `PUT /put` request and returns its commitment.
+    fn respond(&self, request_line: &str, body: Vec<u8>) -> (u16, Vec<u8>) {
+        if let Some(status) = *self.failure.lock().unwrap() {
+            return (status, vec![])
+        }
+        if !request_line.starts_with("PUT /put ") {
+            return (404, vec![])
+        }
+        let commitment = DaServer::commitment(&body);
+        self.inputs.lock().unwrap().insert(commitment.clone(), body);
+        (200, commitment)
+    }
+}
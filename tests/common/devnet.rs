@@ -0,0 +1,199 @@
+use serde_json::{
+    json,
+    Value,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{
+        AsyncBufReadExt,
+        AsyncReadExt,
+        AsyncWriteExt,
+        BufReader,
+    },
+    net::{
+        TcpListener,
+        TcpStream,
+    },
+};
+
+/// A local stand-in for an L1 devnet, answering JSON-RPC calls with canned results.
+#[derive(Debug, Clone, Default)]
+pub struct Devnet {
+    /// The result of each JSON-RPC method
+    results: Arc<Mutex<HashMap<String, Value>>>,
+    /// The result of JSON-RPC calls with specific params, taking precedence over `results`
+    results_by_params: Arc<Mutex<Vec<(String, Value, Value)>>>,
+    /// The number of upcoming calls of each JSON-RPC method to answer with an error
+    failures: Arc<Mutex<HashMap<String, usize>>>,
+    /// The JSON-RPC methods answered with an error when their params contain a string
+    failures_containing: Arc<Mutex<Vec<(String, String)>>>,
+    /// The delay before answering calls of each JSON-RPC method
+    delays: Arc<Mutex<HashMap<String, Duration>>>,
+    /// The JSON-RPC requests received so far
+    requests: Arc<Mutex<Vec<Value>>>,
+}
+
+impl Devnet {
+    /// Answers calls of the JSON-RPC method with the given result.
+    pub fn with_result(self, method: &str, result: Value) -> Self {
+        self.results
+            .lock()
+            .unwrap()
+            .insert(method.to_string(), result);
+        self
+    }
+
+    /// Answers calls of the JSON-RPC method with the given params with the given result,
+    /// replacing any result registered before for the same params.
+    pub fn with_result_for(self, method: &str, params: Value, result: Value) -> Self {
+        self.results_by_params
+            .lock()
+            .unwrap()
+            .push((method.to_string(), params, result));
+        self
+    }
+
+    /// Answers the next `count` calls of the JSON-RPC method with an error.
+    pub fn with_failures(self, method: &str, count: usize) -> Self {
+        self.failures
+            .lock()
+            .unwrap()
+            .insert(method.to_string(), count);
+        self
+    }
+
+    /// Answers calls of the JSON-RPC method whose params contain `needle` with an error.
+    pub fn with_failures_containing(self, method: &str, needle: &str) -> Self {
+        self.failures_containing
+            .lock()
+            .unwrap()
+            .push((method.to_string(), needle.to_string()));
+        self
+    }
+
+    /// Answers calls of the JSON-RPC method only after the given delay.
+    pub fn with_delay(self, method: &str, delay: Duration) -> Self {
+        self.delays
+            .lock()
+            .unwrap()
+            .insert(method.to_string(), delay);
+        self
+    }
+
+    /// Returns the params of every call of the JSON-RPC method received so far.
+    pub fn calls(&self, method: &str) -> Vec<Value> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request["method"] == method)
+            .map(|request| request["params"].clone())
+            .collect()
+    }
+
+    /// Serves JSON-RPC over HTTP on a local port, returning its url.
+    pub async fn serve(&self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let devnet = self.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let devnet = devnet.clone();
+                tokio::spawn(async move { devnet.handle_connection(stream).await });
+            }
+        });
+        url
+    }
+
+    /// Answers the HTTP requests of a keep-alive connection.
+    async fn handle_connection(&self, stream: TcpStream) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        while reader.read_line(&mut line).await? > 0 {
+            let mut content_length = 0;
+            loop {
+                line.clear();
+                reader.read_line(&mut line).await?;
+                let Some((name, value)) = line.trim_end().split_once(':') else {
+                    break
+                };
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or_default();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).await?;
+            let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+            let delay = request["method"]
+                .as_str()
+                .and_then(|method| self.delays.lock().unwrap().get(method).copied());
+            let response = self.respond(request).to_string();
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            reader.get_mut().write_all(response.as_bytes()).await?;
+            line.clear();
+        }
+        Ok(())
+    }
+
+    /// Records the JSON-RPC request and returns its canned response.
+    fn respond(&self, request: Value) -> Value {
+        let method = request["method"].as_str().unwrap_or_default().to_string();
+        let id = request["id"].clone();
+        let by_params = self
+            .results_by_params
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(m, params, _)| *m == method && *params == request["params"])
+            .map(|(_, _, result)| result.clone());
+        let failing =
+            self.failures_containing
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|(m, needle)| {
+                    *m == method && request["params"].to_string().contains(needle)
+                });
+        self.requests.lock().unwrap().push(request);
+        if failing {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32000, "message": "transaction rejected" },
+            })
+        }
+        if let Some(count) = self.failures.lock().unwrap().get_mut(&method) {
+            if *count > 0 {
+                *count -= 1;
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32000, "message": "temporarily unavailable" },
+                })
+            }
+        }
+        match by_params.or_else(|| self.results.lock().unwrap().get(&method).cloned()) {
+            Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            None => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("method {method} not found") },
+            }),
+        }
+    }
+}
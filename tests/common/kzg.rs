@@ -0,0 +1,40 @@
+use archon::blobs::{
+    Blob,
+    Bytes48,
+    KzgCommitter,
+    CELLS_PER_EXT_BLOB,
+};
+use ethers_core::utils::keccak256;
+
+/// Commits to blobs by hashing them, standing in for the KZG trusted setup.
+#[derive(Debug)]
+pub struct MockCommitter;
+
+impl KzgCommitter for MockCommitter {
+    fn blob_to_commitment(&self, blob: &Blob) -> eyre::Result<Bytes48> {
+        let mut commitment = [0u8; 48];
+        commitment[..32].copy_from_slice(&keccak256(blob.as_bytes()));
+        Ok(commitment)
+    }
+
+    fn compute_blob_proof(
+        &self,
+        _: &Blob,
+        commitment: &Bytes48,
+    ) -> eyre::Result<Bytes48> {
+        let mut proof = *commitment;
+        proof.reverse();
+        Ok(proof)
+    }
+
+    fn compute_cell_proofs(&self, blob: &Blob) -> eyre::Result<Vec<Bytes48>> {
+        let commitment = self.blob_to_commitment(blob)?;
+        Ok((0..CELLS_PER_EXT_BLOB as u8)
+            .map(|cell| {
+                let mut proof = commitment;
+                proof[47] = cell;
+                proof
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,5 @@
+#![allow(dead_code)]
+
+pub mod da_server;
+pub mod devnet;
+pub mod kzg;
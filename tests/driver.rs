@@ -0,0 +1,120 @@
+use archon::driver::{
+    DriverEvent,
+    L1Head,
+    L1Window,
+};
+use ethers_core::types::H256;
+
+/// Builds an L1 block on a fork, identified by `fork` in the hash.
+fn l1_head(number: u64, fork: u8, parent_fork: u8) -> L1Head {
+    let hash = |number: u64, fork: u8| {
+        let mut hash = H256::from_low_u64_be(number);
+        hash.0[0] = fork;
+        hash
+    };
+    L1Head {
+        number,
+        hash: hash(number, fork),
+        parent_hash: hash(number.saturating_sub(1), parent_fork),
+    }
+}
+
+fn window_at(tip: u64) -> L1Window {
+    let mut window = L1Window::new(8);
+    for number in 90..=tip {
+        window.extend(vec![l1_head(number, 0, 0)]).unwrap();
+    }
+    window
+}
+
+#[test]
+fn test_new_head_extends_window() {
+    let mut window = window_at(100);
+    let head = l1_head(101, 0, 0);
+    assert_eq!(window.extend(vec![head]), Some(DriverEvent::NewHead(head)));
+    assert_eq!(window.tip(), Some(&head));
+    assert_eq!(window.oldest().unwrap().number, 94);
+}
+
+#[test]
+fn test_repeated_head_is_not_a_reorg() {
+    let mut window = window_at(100);
+    let head = l1_head(100, 0, 0);
+    assert_eq!(window.extend(vec![head]), Some(DriverEvent::NewHead(head)));
+    assert_eq!(window.tip(), Some(&head));
+}
+
+#[test]
+fn test_new_head_after_gap() {
+    let mut window = window_at(100);
+    let chain = vec![l1_head(101, 0, 0), l1_head(102, 0, 0)];
+    let event = window.extend(chain).unwrap();
+    assert_eq!(event, DriverEvent::NewHead(l1_head(102, 0, 0)));
+}
+
+#[test]
+fn test_reorg_at_tip() {
+    let mut window = window_at(100);
+    let head = l1_head(100, 1, 0);
+    assert!(window.contains_parent(&head));
+    assert_eq!(
+        window.extend(vec![head]),
+        Some(DriverEvent::Reorg {
+            common_ancestor: 99,
+            head
+        })
+    );
+    assert_eq!(window.tip(), Some(&head));
+    assert!(!window.contains(&l1_head(100, 0, 0)));
+}
+
+#[test]
+fn test_deeper_reorg() {
+    let mut window = window_at(100);
+    let chain = vec![
+        l1_head(98, 1, 0),
+        l1_head(99, 1, 1),
+        l1_head(100, 1, 1),
+        l1_head(101, 1, 1),
+    ];
+    assert_eq!(
+        window.extend(chain),
+        Some(DriverEvent::Reorg {
+            common_ancestor: 97,
+            head: l1_head(101, 1, 1)
+        })
+    );
+    assert!(window.contains(&l1_head(97, 0, 0)));
+    assert!(window.contains(&l1_head(98, 1, 0)));
+}
+
+#[test]
+fn test_unconnected_head_is_a_reorg() {
+    let mut window = L1Window::new(8);
+    window.extend(vec![l1_head(100, 0, 0)]).unwrap();
+    let head = l1_head(101, 1, 1);
+    assert!(matches!(
+        window.extend(vec![head]),
+        Some(DriverEvent::Reorg { .. })
+    ));
+}
+
+#[test]
+fn test_reorg_to_shorter_chain() {
+    let mut window = window_at(100);
+    let head = l1_head(98, 0, 0);
+    assert_eq!(
+        window.extend(vec![head]),
+        Some(DriverEvent::Reorg {
+            common_ancestor: 98,
+            head
+        })
+    );
+    assert_eq!(window.tip(), Some(&head));
+    assert!(window.get(99).is_none());
+
+    // The chain then grows again from the new head
+    let next = l1_head(99, 1, 0);
+    assert_eq!(window.extend(vec![next]), Some(DriverEvent::NewHead(next)));
+    assert_eq!(window.tip(), Some(&next));
+}
@@ -0,0 +1,81 @@
+use archon::nonce::NonceManager;
+
+#[test]
+fn test_unsynced_nonce_manager() {
+    let nonce_manager = NonceManager::new();
+    assert!(!nonce_manager.is_synced());
+    assert_eq!(nonce_manager.reserve().unwrap(), None);
+}
+
+#[test]
+fn test_sequential_nonces() {
+    let nonce_manager = NonceManager::new();
+    nonce_manager.sync(5).unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(5));
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(6));
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(7));
+}
+
+#[test]
+fn test_sync_keeps_handed_out_nonces() {
+    let nonce_manager = NonceManager::new();
+    nonce_manager.sync(5).unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(5));
+    // A concurrent sync from a stale pending count must not hand out 5 again.
+    nonce_manager.sync(5).unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(6));
+}
+
+#[test]
+fn test_reset_resyncs_nonce() {
+    let nonce_manager = NonceManager::new();
+    nonce_manager.sync(5).unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(5));
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(6));
+
+    // Nonce 6 was never published, so L1 still reports 6 pending transactions.
+    nonce_manager.reset().unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), None);
+    nonce_manager.sync(6).unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(6));
+}
+
+#[test]
+fn test_clones_share_nonce() {
+    let nonce_manager = NonceManager::new();
+    let shared = nonce_manager.clone();
+    nonce_manager.sync(1).unwrap();
+    assert_eq!(shared.reserve().unwrap(), Some(1));
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(2));
+    shared.reset().unwrap();
+    assert!(!nonce_manager.is_synced());
+}
+
+#[test]
+fn test_released_nonce_is_handed_out_first() {
+    let nonce_manager = NonceManager::new();
+    nonce_manager.sync(5).unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(5));
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(6));
+
+    // Nonce 5 failed to send while 6 is in flight, so 5 fills the gap next
+    nonce_manager.release(5).unwrap();
+    assert!(nonce_manager.is_synced());
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(5));
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(7));
+    assert_eq!(nonce_manager.in_flight(), 3);
+}
+
+#[test]
+fn test_release_resyncs_once_nothing_is_in_flight() {
+    let nonce_manager = NonceManager::new();
+    nonce_manager.sync(5).unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(5));
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(6));
+    nonce_manager.settle(5).unwrap();
+
+    // No later nonce is in flight, so L1's pending count is accurate again
+    nonce_manager.release(6).unwrap();
+    assert!(!nonce_manager.is_synced());
+    assert_eq!(nonce_manager.in_flight(), 0);
+}
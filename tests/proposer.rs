@@ -0,0 +1,518 @@
+use std::{
+    str::FromStr,
+    time::Duration,
+};
+
+use archon::{
+    channels::TransactionID,
+    client::Archon,
+    config::Config,
+    errors::ConfigError,
+    nonce::NonceManager,
+    proposer::{
+        DisputeGameConfig,
+        GameClaim,
+        OutputProposal,
+        ProposalTarget,
+        Proposer,
+        ProposerConfig,
+        CREATE_GAME_SELECTOR,
+        GAMES_SELECTOR,
+        NEXT_BLOCK_NUMBER_SELECTOR,
+        PROPOSE_L2_OUTPUT_SELECTOR,
+    },
+    rollup::{
+        BlockNumHash,
+        L1BlockRef,
+        L2BlockRef,
+        OutputResponse,
+        RollupNode,
+        SyncStatus,
+    },
+    transactions::{
+        DataAvailabilityType,
+        TransactionConfig,
+        TransactionManager,
+    },
+};
+use bytes::Bytes;
+use ethers_core::{
+    types::{
+        Address,
+        H256,
+        U256,
+    },
+    utils::keccak256,
+};
+use ethers_providers::{
+    Http,
+    Provider,
+};
+use ethers_signers::{
+    LocalWallet,
+    Signer,
+};
+use serde_json::json;
+
+mod common;
+
+use common::devnet::Devnet;
+
+const PROPOSER_PRIVATE_KEY: &str =
+    "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72";
+
+fn output(l2_block_number: u64, current_l1: u64) -> OutputResponse {
+    OutputResponse {
+        version: H256::zero(),
+        output_root: H256::repeat_byte(0xab),
+        block_ref: L2BlockRef {
+            hash: H256::repeat_byte(0x01),
+            number: l2_block_number,
+            parent_hash: H256::repeat_byte(0x02),
+            timestamp: 1_700_000_000,
+            l1_origin: BlockNumHash {
+                hash: H256::repeat_byte(current_l1 as u8 - 2),
+                number: current_l1 - 2,
+            },
+            sequence_number: 0,
+        },
+        withdrawal_storage_root: H256::repeat_byte(0x03),
+        state_root: H256::repeat_byte(0x04),
+        sync_status: SyncStatus {
+            current_l1: l1_ref(current_l1),
+            ..Default::default()
+        },
+    }
+}
+
+fn l1_ref(number: u64) -> L1BlockRef {
+    L1BlockRef {
+        hash: H256::repeat_byte(number as u8),
+        number,
+        parent_hash: H256::repeat_byte(number as u8 - 1),
+        timestamp: 1_700_000_000,
+    }
+}
+
+fn l2_ref(number: u64) -> L2BlockRef {
+    L2BlockRef {
+        number,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_selectors() {
+    assert_eq!(
+        PROPOSE_L2_OUTPUT_SELECTOR,
+        keccak256("proposeL2Output(bytes32,uint256,bytes32,uint256)")[..4]
+    );
+    assert_eq!(
+        NEXT_BLOCK_NUMBER_SELECTOR,
+        keccak256("nextBlockNumber()")[..4]
+    );
+    assert_eq!(
+        CREATE_GAME_SELECTOR,
+        keccak256("create(uint32,bytes32,bytes)")[..4]
+    );
+    assert_eq!(
+        GAMES_SELECTOR,
+        keccak256("games(uint32,bytes32,bytes)")[..4]
+    );
+}
+
+fn dispute_game_config() -> DisputeGameConfig {
+    DisputeGameConfig {
+        factory: Address::repeat_byte(0x0f),
+        game_type: 1,
+        bond: U256::from(80_000_000_000_000_000u64),
+        submission_interval: 100,
+    }
+}
+
+#[test]
+fn test_dispute_game_claim_block() {
+    let game = dispute_game_config();
+    assert_eq!(game.claim_block(99), None);
+    assert_eq!(game.claim_block(100), Some(100));
+    assert_eq!(game.claim_block(1899), Some(1800));
+}
+
+#[test]
+fn test_game_claim_calldata() {
+    let claim = GameClaim::new(1, &output(1800, 0x20));
+    assert_eq!(claim.root_claim, H256::repeat_byte(0xab));
+    assert_eq!(claim.l2_block_number, 1800);
+
+    let calldata = claim.create_calldata();
+    assert_eq!(calldata.len(), 4 + 5 * 32);
+    assert_eq!(calldata[..4], CREATE_GAME_SELECTOR);
+    assert_eq!(U256::from_big_endian(&calldata[4..36]), U256::one());
+    assert_eq!(calldata[36..68], [0xab; 32]);
+    // The extra data is a dynamic bytes argument holding the L2 block number
+    assert_eq!(U256::from_big_endian(&calldata[68..100]), U256::from(96));
+    assert_eq!(U256::from_big_endian(&calldata[100..132]), U256::from(32));
+    assert_eq!(U256::from_big_endian(&calldata[132..164]), U256::from(1800));
+
+    let games_calldata = claim.games_calldata();
+    assert_eq!(games_calldata[..4], GAMES_SELECTOR);
+    assert_eq!(games_calldata[4..], calldata[4..]);
+}
+
+#[tokio::test]
+async fn test_dispute_game_proposals() {
+    let game = dispute_game_config();
+    let rollup = Devnet::default()
+        .with_result(
+            "optimism_syncStatus",
+            serde_json::to_value(SyncStatus {
+                finalized_l2: l2_ref(1899),
+                ..Default::default()
+            })
+            .unwrap(),
+        )
+        .with_result(
+            "optimism_outputAtBlock",
+            serde_json::to_value(output(1800, 0x20)).unwrap(),
+        );
+    let no_game = format!("0x{}", "00".repeat(64));
+    let l1 = Devnet::default().with_result("eth_call", json!(no_game));
+    let rollup_node = RollupNode::new(&rollup.serve().await).unwrap();
+    let provider = Provider::<Http>::try_from(l1.serve().await).unwrap();
+    let config = ProposerConfig {
+        target: ProposalTarget::DisputeGameFactory(game),
+        ..Default::default()
+    };
+
+    // A dispute game is created for the claim at the latest submission interval
+    let claim = GameClaim::new(game.game_type, &output(1800, 0x20));
+    let (l2_block_number, calldata) =
+        Proposer::next_proposal(&config, &rollup_node, &provider)
+            .await
+            .unwrap()
+            .unwrap();
+    assert_eq!(l2_block_number, 1800);
+    assert_eq!(calldata, claim.create_calldata());
+    assert_eq!(rollup.calls("optimism_outputAtBlock")[0], json!(["0x708"]));
+    let calls = l1.calls("eth_call");
+    assert_eq!(calls[0][0]["to"], json!(format!("{:?}", game.factory)));
+    assert_eq!(
+        calls[0][0]["data"],
+        json!(format!("0x{}", hex::encode(claim.games_calldata())))
+    );
+
+    // Existing games are not duplicated
+    let existing_game =
+        format!("0x{:0>64}{:064x}", hex::encode([0x99; 20]), 1_700_000_000);
+    let l1 = Devnet::default().with_result("eth_call", json!(existing_game));
+    let provider = Provider::<Http>::try_from(l1.serve().await).unwrap();
+    assert!(Proposer::game_exists(&provider, game.factory, &claim)
+        .await
+        .unwrap());
+    assert_eq!(
+        Proposer::next_proposal(&config, &rollup_node, &provider)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_dispute_game_proposer_config() {
+    let config = Config {
+        l2_output_oracle: Some(Address::repeat_byte(0x0c)),
+        ..Default::default()
+    };
+    assert_eq!(
+        config.proposer_config().unwrap().target,
+        ProposalTarget::L2OutputOracle(Address::repeat_byte(0x0c))
+    );
+    assert_eq!(config.proposer_transaction_config().value, U256::zero());
+
+    // The DisputeGameFactory takes precedence, and games are created with the bond
+    let config = Config {
+        dispute_game_factory: Some(Address::repeat_byte(0x0f)),
+        game_type: 1,
+        game_bond: U256::from(1000),
+        submission_interval: 100,
+        ..config
+    };
+    let proposer_config = config.proposer_config().unwrap();
+    assert_eq!(
+        proposer_config.target,
+        ProposalTarget::DisputeGameFactory(DisputeGameConfig {
+            factory: Address::repeat_byte(0x0f),
+            game_type: 1,
+            bond: U256::from(1000),
+            submission_interval: 100,
+        })
+    );
+    assert_eq!(proposer_config.target.address(), Address::repeat_byte(0x0f));
+    assert_eq!(config.proposer_transaction_config().value, U256::from(1000));
+    assert!(Config::default().proposer_config().is_none());
+}
+
+#[test]
+fn test_output_proposal_calldata() {
+    let proposal = OutputProposal::new(&output(1800, 0x20));
+    assert_eq!(proposal.output_root, H256::repeat_byte(0xab));
+    assert_eq!(proposal.l2_block_number, 1800);
+
+    let calldata = proposal.calldata();
+    assert_eq!(calldata.len(), 4 + 4 * 32);
+    assert_eq!(calldata[..4], PROPOSE_L2_OUTPUT_SELECTOR);
+    assert_eq!(calldata[4..36], [0xab; 32]);
+    assert_eq!(U256::from_big_endian(&calldata[36..68]), U256::from(1800));
+    assert_eq!(calldata[68..100], [0x20; 32]);
+    assert_eq!(U256::from_big_endian(&calldata[100..132]), U256::from(0x20));
+}
+
+#[test]
+fn test_proposer_l2_head() {
+    let sync_status = SyncStatus {
+        safe_l2: l2_ref(120),
+        finalized_l2: l2_ref(100),
+        ..Default::default()
+    };
+    let config = ProposerConfig::default();
+    assert_eq!(config.l2_head(&sync_status), 100);
+    let config = ProposerConfig {
+        allow_non_finalized: true,
+        ..config
+    };
+    assert_eq!(config.l2_head(&sync_status), 120);
+}
+
+#[test]
+fn test_output_transaction_id() {
+    assert_eq!(TransactionID::output(1800).to_string(), "output:1800");
+}
+
+#[tokio::test]
+async fn test_next_block_number() {
+    let oracle = Address::repeat_byte(0x0c);
+    let devnet =
+        Devnet::default().with_result("eth_call", json!(format!("0x{:064x}", 1800)));
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+
+    let next = Proposer::next_block_number(&provider, oracle)
+        .await
+        .unwrap();
+    assert_eq!(next, 1800);
+    let calls = devnet.calls("eth_call");
+    assert_eq!(calls[0][0]["to"], json!(format!("{oracle:?}")));
+    assert_eq!(
+        calls[0][0]["data"],
+        json!(format!("0x{}", hex::encode(NEXT_BLOCK_NUMBER_SELECTOR)))
+    );
+}
+
+#[tokio::test]
+async fn test_proposal_from_rollup_node() {
+    let rollup = Devnet::default().with_result(
+        "optimism_outputAtBlock",
+        serde_json::to_value(output(1800, 0x20)).unwrap(),
+    );
+    let rollup_node = RollupNode::new(&rollup.serve().await).unwrap();
+
+    // The proposal is anchored to the L1 block the rollup node derived the output from
+    let proposal = Proposer::proposal(&rollup_node, 1800).await.unwrap();
+    assert_eq!(proposal.l1_block_number, 0x20);
+    assert_eq!(proposal.l1_block_hash, H256::repeat_byte(0x20));
+    assert_eq!(rollup.calls("optimism_outputAtBlock")[0], json!(["0x708"]));
+}
+
+#[test]
+fn test_proposer_shares_nonces_with_batcher_account() {
+    let config = Config {
+        l2_output_oracle: Some(Address::repeat_byte(0x0c)),
+        ..Default::default()
+    };
+    assert_eq!(config.proposer_address, config.batcher_address);
+    let archon = Archon::new(Some(config.clone()));
+    let proposer = archon.build_proposer().unwrap().unwrap();
+    let nonce_manager = proposer.transaction_manager().unwrap().nonce_manager();
+    archon.nonce_manager().sync(3).unwrap();
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(3));
+    assert_eq!(archon.nonce_manager().reserve().unwrap(), Some(4));
+
+    // A separate proposer account has its own nonces
+    let archon = Archon::new(Some(Config {
+        proposer_address: Address::repeat_byte(0x01),
+        ..config
+    }));
+    let proposer = archon.build_proposer().unwrap().unwrap();
+    archon.nonce_manager().sync(3).unwrap();
+    assert!(!proposer
+        .transaction_manager()
+        .unwrap()
+        .nonce_manager()
+        .is_synced());
+}
+
+#[test]
+fn test_proposer_cannot_share_batcher_account_posting_blobs() {
+    for data_availability_type in
+        [DataAvailabilityType::Blobs, DataAvailabilityType::Auto]
+    {
+        let config = Config {
+            l2_output_oracle: Some(Address::repeat_byte(0x0c)),
+            data_availability_type,
+            ..Default::default()
+        };
+        let err = Archon::new(Some(config.clone()))
+            .build_proposer()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConfigError>(),
+            Some(ConfigError::SharedBlobAccount(address)) if *address == config.batcher_address
+        ));
+
+        // A separate proposer account posts calldata alongside the batcher's blobs
+        let archon = Archon::new(Some(Config {
+            proposer_address: Address::repeat_byte(0x01),
+            ..config
+        }));
+        assert!(archon.build_proposer().unwrap().is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_craft_transaction_with_estimated_gas() {
+    let devnet = Devnet::default()
+        .with_result(
+            "eth_getBlockByNumber",
+            json!({ "number": "0x10", "baseFeePerGas": "0x9502f900" }),
+        )
+        .with_result("eth_maxPriorityFeePerGas", json!("0x3b9aca00"))
+        .with_result("eth_getTransactionCount", json!("0x7"))
+        .with_result("eth_estimateGas", json!("0x186a0"));
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let config = TransactionConfig {
+        estimate_gas: true,
+        ..Default::default()
+    };
+
+    let tx = TransactionManager::craft_transaction(
+        5,
+        Address::repeat_byte(0x0c),
+        Address::repeat_byte(0x01),
+        &provider,
+        &config,
+        &NonceManager::new(),
+        Bytes::from_static(&[0xff; 132]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(tx.gas(), Some(&U256::from(100_000)));
+    assert_eq!(devnet.calls("eth_estimateGas").len(), 1);
+}
+
+#[tokio::test]
+async fn test_craft_transaction_failure_keeps_nonce() {
+    let devnet = Devnet::default()
+        .with_result(
+            "eth_getBlockByNumber",
+            json!({ "number": "0x10", "baseFeePerGas": "0x9502f900" }),
+        )
+        .with_result("eth_maxPriorityFeePerGas", json!("0x3b9aca00"));
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let config = TransactionConfig {
+        estimate_gas: true,
+        ..Default::default()
+    };
+    let nonce_manager = NonceManager::new();
+    nonce_manager.sync(7).unwrap();
+
+    // Gas estimation fails, so the nonce must not be handed out
+    let result = TransactionManager::craft_transaction(
+        5,
+        Address::repeat_byte(0x0c),
+        Address::repeat_byte(0x01),
+        &provider,
+        &config,
+        &nonce_manager,
+        Bytes::from_static(&[0xff; 132]),
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(nonce_manager.reserve().unwrap(), Some(7));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_proposer_submits_output() {
+    let oracle = Address::repeat_byte(0x0c);
+    let rollup = Devnet::default()
+        .with_result(
+            "optimism_syncStatus",
+            serde_json::to_value(SyncStatus {
+                current_l1: l1_ref(0x20),
+                finalized_l2: l2_ref(1800),
+                ..Default::default()
+            })
+            .unwrap(),
+        )
+        .with_result(
+            "optimism_outputAtBlock",
+            serde_json::to_value(output(1800, 0x20)).unwrap(),
+        );
+    let l1 = Devnet::default()
+        .with_result("eth_call", json!(format!("0x{:064x}", 1800)))
+        .with_result(
+            "eth_getBlockByNumber",
+            json!({
+                "number": "0x20",
+                "hash": format!("{:?}", H256::repeat_byte(0x20)),
+                "baseFeePerGas": "0x9502f900",
+            }),
+        )
+        .with_result("eth_maxPriorityFeePerGas", json!("0x3b9aca00"))
+        .with_result("eth_getTransactionCount", json!("0x0"))
+        .with_result("eth_estimateGas", json!("0x186a0"))
+        .with_result(
+            "eth_sendRawTransaction",
+            json!(format!("{:?}", H256::repeat_byte(0xaa))),
+        );
+    let provider = Provider::<Http>::try_from(l1.serve().await).unwrap();
+    let proposer_address = LocalWallet::from_str(PROPOSER_PRIVATE_KEY)
+        .unwrap()
+        .address();
+    let mut tx_manager = TransactionManager::new(
+        Some(5),
+        Some(oracle),
+        Some(proposer_address),
+        Some(PROPOSER_PRIVATE_KEY.to_string()),
+        provider.clone(),
+    );
+    tx_manager.with_transaction_config(TransactionConfig {
+        estimate_gas: true,
+        ..Default::default()
+    });
+    let config = ProposerConfig {
+        target: ProposalTarget::L2OutputOracle(oracle),
+        ..Default::default()
+    };
+    let mut proposer = Proposer::new(
+        config,
+        RollupNode::new(&rollup.serve().await).unwrap(),
+        provider,
+    );
+    proposer.with_transaction_manager(tx_manager);
+    let _handle = proposer.spawn().unwrap();
+
+    let mut published = vec![];
+    for _ in 0..50 {
+        published = l1.calls("eth_sendRawTransaction");
+        if !published.is_empty() {
+            break
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(published.len(), 1);
+
+    // The published transaction carries the proposal calldata
+    let proposal = OutputProposal::new(&output(1800, 0x20));
+    let raw = published[0][0].as_str().unwrap().to_string();
+    assert!(raw.contains(&hex::encode(proposal.calldata())));
+    assert_eq!(rollup.calls("optimism_outputAtBlock")[0], json!(["0x708"]));
+}
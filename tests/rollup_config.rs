@@ -0,0 +1,141 @@
+use std::str::FromStr;
+
+use archon::{
+    config::Config,
+    errors::ConfigError,
+    rollup::{
+        RollupConfig,
+        RollupNode,
+        CHANNEL_TIMEOUT_GRANITE,
+    },
+};
+use ethers_core::types::{
+    Address,
+    H256,
+};
+
+mod common;
+
+fn op_mainnet() -> RollupConfig {
+    serde_json::from_str(include_str!("fixtures/op_mainnet_rollup_config.json")).unwrap()
+}
+
+fn op_mainnet_config() -> Config {
+    Config {
+        batcher_inbox: Address::from_str("0xff00000000000000000000000000000000000010")
+            .unwrap(),
+        channel_timeout: 50,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_deserialize_rollup_config() {
+    let rollup_config = op_mainnet();
+    assert_eq!(rollup_config.genesis.l1.number, 17422590);
+    assert_eq!(
+        rollup_config.genesis.l2.hash,
+        H256::from_str(
+            "0xdbf6a80fef073de06add9b0d14026d6e5a86c85f6d102c36d3d8e9cf89c2afd3"
+        )
+        .unwrap()
+    );
+    assert_eq!(rollup_config.genesis.l2_time, 1686068903);
+    assert_eq!(rollup_config.genesis.system_config.gas_limit, 30_000_000);
+    assert_eq!(rollup_config.block_time, 2);
+    assert_eq!(rollup_config.max_sequencer_drift, 600);
+    assert_eq!(rollup_config.seq_window_size, 3600);
+    assert_eq!(rollup_config.channel_timeout, 300);
+    assert_eq!(rollup_config.l1_chain_id, 1);
+    assert_eq!(rollup_config.l2_chain_id, 10);
+    assert_eq!(rollup_config.ecotone_time, Some(1710374401));
+    assert!(!rollup_config.is_delta(1708559999));
+    assert!(rollup_config.is_delta(1708560000));
+    assert!(rollup_config.is_ecotone(1710374401));
+    assert!(!rollup_config.is_granite(1726070400));
+    assert!(rollup_config.is_granite(1726070401));
+    assert_eq!(rollup_config.holocene_time, Some(1736445601));
+    assert_eq!(rollup_config.isthmus_time, Some(1746806401));
+    assert_eq!(rollup_config.jovian_time, Some(1764691201));
+    assert_eq!(rollup_config.interop_time, None);
+
+    // Unscheduled hardforks are omitted
+    let mut value = serde_json::to_value(&rollup_config).unwrap();
+    value.as_object_mut().unwrap().remove("jovian_time");
+    let rollup_config: RollupConfig = serde_json::from_value(value).unwrap();
+    assert_eq!(rollup_config.jovian_time, None);
+    assert!(serde_json::to_value(&rollup_config)
+        .unwrap()
+        .get("jovian_time")
+        .is_none());
+}
+
+#[test]
+fn test_apply_rollup_config() {
+    let mut config = op_mainnet_config();
+    config.apply_rollup_config(&op_mainnet()).unwrap();
+    assert_eq!(config.channel_timeout, 300);
+    assert_eq!(config.granite_time, Some(1726070401));
+
+    // Channels switch to the Granite timeout with the L2 block timestamp, not at startup
+    let channel_config = config.channel_config();
+    assert_eq!(channel_config.channel_timeout, 300);
+    assert_eq!(channel_config.channel_timeout_at(1726070400), 300);
+    assert_eq!(
+        channel_config.channel_timeout_at(1726070401),
+        CHANNEL_TIMEOUT_GRANITE
+    );
+}
+
+#[test]
+fn test_channel_timeout_at_granite() {
+    let rollup_config = op_mainnet();
+    assert_eq!(rollup_config.channel_timeout_at(1726070400), 300);
+    assert_eq!(rollup_config.channel_timeout_at(1726070401), 50);
+}
+
+#[test]
+fn test_apply_rollup_config_mismatch() {
+    let mut config = Config::default();
+    let err = config.apply_rollup_config(&op_mainnet()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ConfigError>(),
+        Some(ConfigError::BatchInboxMismatch(..))
+    ));
+
+    let rollup_config = RollupConfig {
+        l1_chain_id: 5,
+        ..op_mainnet()
+    };
+    let err = op_mainnet_config()
+        .apply_rollup_config(&rollup_config)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ConfigError>(),
+        Some(ConfigError::L1ChainIdMismatch(1, 5))
+    ));
+
+    let rollup_config = RollupConfig {
+        l2_chain_id: 8453,
+        ..op_mainnet()
+    };
+    let mut config = op_mainnet_config();
+    let err = config.apply_rollup_config(&rollup_config).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ConfigError>(),
+        Some(ConfigError::L2ChainIdMismatch(10, 8453))
+    ));
+    // The channel timeout is left untouched
+    assert_eq!(config.channel_timeout, 50);
+}
+
+#[tokio::test]
+async fn test_fetch_rollup_config() {
+    let rollup = common::devnet::Devnet::default().with_result(
+        "optimism_rollupConfig",
+        serde_json::from_str(include_str!("fixtures/op_mainnet_rollup_config.json"))
+            .unwrap(),
+    );
+    let rollup_node = RollupNode::new(&rollup.serve().await).unwrap();
+    assert_eq!(rollup_node.rollup_config().await.unwrap(), op_mainnet());
+}
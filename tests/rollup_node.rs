@@ -1,13 +1,3 @@
-use ethers_core::types::{
-    BlockId,
-    BlockNumber,
-};
-use ethers_providers::{
-    Http,
-    Middleware,
-    Provider,
-};
-
 use archon::rollup::RollupNode;
 
 /// Requires the following environment variables to be set: ROLLUP_NODE_RPC_URL
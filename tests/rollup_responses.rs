@@ -0,0 +1,144 @@
+use archon::rollup::{
+    OutputResponse,
+    RollupNode,
+    SyncStatus,
+};
+use ethers_core::{
+    types::H256,
+    utils::keccak256,
+};
+use serde_json::{
+    json,
+    Value,
+};
+
+mod common;
+
+/// The timestamp of L1 block 0, which is also the timestamp of L2 block 0.
+const GENESIS_TIME: u64 = 1_700_000_000;
+
+/// Returns the hash of the block, with L1 hashes prefixed by 0x01 and L2 hashes by 0x02.
+fn hash(layer: u8, number: u64) -> H256 {
+    let mut hash = H256::from_low_u64_be(number);
+    hash.0[0] = layer;
+    hash
+}
+
+/// Builds the JSON of an L1 block ref, with a 12 second block time.
+fn l1_ref(number: u64) -> Value {
+    json!({
+        "hash": hash(1, number),
+        "number": number,
+        "parentHash": hash(1, number - 1),
+        "timestamp": GENESIS_TIME + 12 * number,
+    })
+}
+
+/// Builds the JSON of an L2 block ref, with a 2 second block time.
+/// Its L1 origin is the latest L1 block at or before its timestamp.
+fn l2_ref(number: u64) -> Value {
+    json!({
+        "hash": hash(2, number),
+        "number": number,
+        "parentHash": hash(2, number - 1),
+        "timestamp": GENESIS_TIME + 2 * number,
+        "l1origin": { "hash": hash(1, number / 6), "number": number / 6 },
+        "sequenceNumber": number % 6,
+    })
+}
+
+/// Builds the JSON of a sync status, as reported by op-node before interop,
+/// where the cross-unsafe and local-safe heads equal the unsafe and safe heads.
+fn sync_status_json() -> Value {
+    json!({
+        "current_l1": l1_ref(108),
+        "current_l1_finalized": l1_ref(96),
+        "head_l1": l1_ref(110),
+        "safe_l1": l1_ref(104),
+        "finalized_l1": l1_ref(96),
+        "unsafe_l2": l2_ref(655),
+        "safe_l2": l2_ref(631),
+        "finalized_l2": l2_ref(570),
+        "pending_safe_l2": l2_ref(631),
+        "cross_unsafe_l2": l2_ref(655),
+        "local_safe_l2": l2_ref(631),
+    })
+}
+
+/// Builds the JSON of the output at the finalized L2 head, with its version 0 output root.
+fn output_json() -> Value {
+    let state_root = H256::repeat_byte(0x03);
+    let withdrawal_storage_root = H256::repeat_byte(0x04);
+    let mut preimage = H256::zero().as_bytes().to_vec();
+    preimage.extend_from_slice(state_root.as_bytes());
+    preimage.extend_from_slice(withdrawal_storage_root.as_bytes());
+    preimage.extend_from_slice(hash(2, 570).as_bytes());
+    json!({
+        "version": H256::zero(),
+        "outputRoot": H256::from(keccak256(preimage)),
+        "blockRef": l2_ref(570),
+        "withdrawalStorageRoot": withdrawal_storage_root,
+        "stateRoot": state_root,
+        "syncStatus": sync_status_json(),
+    })
+}
+
+#[test]
+fn test_sync_status_round_trip() {
+    let sync_status: SyncStatus = serde_json::from_value(sync_status_json()).unwrap();
+    assert_eq!(sync_status.current_l1.number, 108);
+    assert_eq!(sync_status.head_l1.number, 110);
+    assert_eq!(sync_status.unsafe_l2.number, 655);
+    assert_eq!(sync_status.unsafe_l2.l1_origin.number, 109);
+    assert_eq!(sync_status.unsafe_l2.sequence_number, 1);
+    assert_eq!(sync_status.safe_l2, sync_status.pending_safe_l2);
+    assert_eq!(sync_status.finalized_l2.number, 570);
+    assert_eq!(
+        serde_json::to_value(sync_status).unwrap(),
+        sync_status_json()
+    );
+}
+
+#[test]
+fn test_sync_status_interop_heads() {
+    let sync_status: SyncStatus = serde_json::from_value(sync_status_json()).unwrap();
+    assert_eq!(sync_status.cross_unsafe_l2, sync_status.unsafe_l2);
+    assert_eq!(sync_status.local_safe_l2, sync_status.safe_l2);
+
+    // The heads are always reported by op-node
+    let mut value = sync_status_json();
+    value.as_object_mut().unwrap().remove("cross_unsafe_l2");
+    assert!(serde_json::from_value::<SyncStatus>(value).is_err());
+}
+
+#[test]
+fn test_output_response_round_trip() {
+    let output: OutputResponse = serde_json::from_value(output_json()).unwrap();
+    assert_eq!(output.version, H256::zero());
+    assert_eq!(
+        output.output_root,
+        serde_json::from_value::<H256>(output_json()["outputRoot"].clone()).unwrap()
+    );
+    assert_eq!(output.block_ref.number, 570);
+    assert_eq!(output.block_ref, output.sync_status.finalized_l2);
+    assert_eq!(output.sync_status.current_l1.number, 108);
+    assert_eq!(serde_json::to_value(output).unwrap(), output_json());
+}
+
+#[tokio::test]
+async fn test_fetch_sync_status_and_output() {
+    let rollup = common::devnet::Devnet::default()
+        .with_result("optimism_syncStatus", sync_status_json())
+        .with_result("optimism_outputAtBlock", output_json());
+    let rollup_node = RollupNode::new(&rollup.serve().await).unwrap();
+
+    let sync_status = rollup_node.sync_status().await.unwrap();
+    assert_eq!(
+        serde_json::to_value(sync_status).unwrap(),
+        sync_status_json()
+    );
+
+    let output = rollup_node.output_at_block(570).await.unwrap();
+    assert_eq!(serde_json::to_value(output).unwrap(), output_json());
+    assert_eq!(rollup.calls("optimism_outputAtBlock")[0], json!(["0x23a"]));
+}
@@ -0,0 +1,557 @@
+use archon::{
+    channels::{
+        TaggedData,
+        TransactionID,
+    },
+    config::Config,
+    errors::TransactionManagerError,
+    nonce::NonceManager,
+    transactions::{
+        calldata_gas,
+        gwei,
+        BatcherTransaction,
+        Cancellation,
+        DataAvailabilityType,
+        TransactionConfig,
+        TransactionManager,
+        TransactionOutcome,
+    },
+};
+use ethers_core::{
+    types::{
+        transaction::eip2718::TypedTransaction,
+        Address,
+        Eip1559TransactionRequest,
+        TransactionReceipt,
+        H256,
+        U256,
+        U64,
+    },
+    utils::rlp::Rlp,
+};
+use ethers_providers::{
+    Http,
+    Provider,
+};
+use ethers_signers::{
+    LocalWallet,
+    Signer,
+};
+use serde_json::{
+    json,
+    Value,
+};
+use std::{
+    str::FromStr,
+    sync::{
+        mpsc,
+        Arc,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+mod common;
+
+use common::kzg::MockCommitter;
+
+#[test]
+fn test_sender_wallet_from_default_config() {
+    let config = Config::default();
+    let wallet = TransactionManager::sender_wallet(
+        &config.batcher_private_key,
+        config.l1_chain_id(),
+        config.batcher_address,
+    )
+    .unwrap();
+    assert_eq!(wallet.address(), config.batcher_address);
+    assert_eq!(wallet.chain_id(), 1);
+}
+
+#[test]
+fn test_sender_wallet_rejects_mismatched_address() {
+    let config = Config::default();
+    let err = TransactionManager::sender_wallet(
+        &config.batcher_private_key,
+        config.l1_chain_id(),
+        Address::repeat_byte(0x11),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<TransactionManagerError>(),
+        Some(TransactionManagerError::SenderAddressMismatch)
+    ));
+}
+
+#[test]
+fn test_sender_wallet_rejects_invalid_key() {
+    let err =
+        TransactionManager::sender_wallet("0x1234", 1, Address::zero()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<TransactionManagerError>(),
+        Some(TransactionManagerError::InvalidSenderPrivateKey)
+    ));
+}
+
+#[test]
+fn test_max_fee_per_gas() {
+    let config = TransactionConfig {
+        base_fee_multiplier: 2,
+        ..Default::default()
+    };
+    let max_fee = config
+        .max_fee_per_gas(U256::from(30_000_000_000u64), U256::from(1_000_000_000u64));
+    assert_eq!(max_fee, U256::from(61_000_000_000u64));
+}
+
+#[test]
+fn test_calldata_gas() {
+    assert_eq!(calldata_gas(&[]), 21_000);
+    // Standard cost: 21000 + 4 * 4 + 16 * 4 = 21080, floor: 21000 + 10 * 20 = 21200.
+    assert_eq!(calldata_gas(&[0, 0, 0, 0, 1, 2, 3, 4]), 21_200);
+    assert_eq!(calldata_gas(&vec![0xff; 1_000]), 21_000 + 40 * 1_000);
+}
+
+#[test]
+fn test_bump_fees_by_replacement_percentage() {
+    let config = TransactionConfig::default();
+    let (max_fee, tip) = config
+        .bump_fees(
+            U256::from(101u64),
+            U256::from(11u64),
+            U256::zero(),
+            U256::zero(),
+        )
+        .unwrap();
+    // 10% bumps round up so the replacement is never underpriced.
+    assert_eq!(max_fee, U256::from(112u64));
+    assert_eq!(tip, U256::from(13u64));
+}
+
+#[test]
+fn test_bump_blob_tx_fees_doubles_fees() {
+    let config = TransactionConfig::default();
+    let (max_fee, tip) = config
+        .bump_blob_tx_fees(gwei(20), gwei(1), gwei(10), gwei(1))
+        .unwrap();
+    assert_eq!(max_fee, gwei(40));
+    assert_eq!(tip, gwei(2));
+    assert!(TransactionConfig {
+        fee_ceiling: gwei(30),
+        ..Default::default()
+    }
+    .bump_blob_tx_fees(gwei(20), gwei(1), gwei(0), gwei(0))
+    .is_none());
+}
+
+#[test]
+fn test_bump_fees_follows_suggested_fees() {
+    let config = TransactionConfig::default();
+    let (max_fee, tip) = config
+        .bump_fees(gwei(10), gwei(1), gwei(40), gwei(2))
+        .unwrap();
+    assert_eq!(max_fee, gwei(40));
+    assert_eq!(tip, gwei(2));
+}
+
+#[test]
+fn test_bump_fees_stops_at_ceiling() {
+    let config = TransactionConfig {
+        fee_ceiling: gwei(100),
+        ..Default::default()
+    };
+    assert!(config
+        .bump_fees(gwei(90), gwei(1), gwei(0), gwei(0))
+        .is_some());
+    assert!(config
+        .bump_fees(gwei(91), gwei(1), gwei(0), gwei(0))
+        .is_none());
+    assert!(config
+        .bump_fees(gwei(10), gwei(1), gwei(101), gwei(1))
+        .is_none());
+}
+
+#[test]
+fn test_default_config_matches_default_transaction_config() {
+    let config = Config::default();
+    assert_eq!(config.transaction_config(), TransactionConfig::default());
+    assert_eq!(config.transaction_config().num_confirmations, 6);
+}
+
+fn receipt(status: u64, to: Address) -> TransactionReceipt {
+    TransactionReceipt {
+        status: Some(U64::from(status)),
+        to: Some(to),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_check_receipt_accepts_successful_batch() {
+    let inbox = Address::repeat_byte(0xff);
+    assert!(TransactionManager::check_receipt(&receipt(1, inbox), inbox).is_ok());
+}
+
+#[test]
+fn test_check_receipt_rejects_reverted_transaction() {
+    let inbox = Address::repeat_byte(0xff);
+    assert!(matches!(
+        TransactionManager::check_receipt(&receipt(0, inbox), inbox),
+        Err(TransactionManagerError::TransactionReverted)
+    ));
+    let missing_status = TransactionReceipt {
+        to: Some(inbox),
+        ..Default::default()
+    };
+    assert!(matches!(
+        TransactionManager::check_receipt(&missing_status, inbox),
+        Err(TransactionManagerError::TransactionReverted)
+    ));
+}
+
+#[test]
+fn test_check_receipt_rejects_unexpected_recipient() {
+    let inbox = Address::repeat_byte(0xff);
+    let other = Address::repeat_byte(0x11);
+    assert!(matches!(
+        TransactionManager::check_receipt(&receipt(1, other), inbox),
+        Err(TransactionManagerError::UnexpectedRecipient(Some(to))) if to == other
+    ));
+}
+
+#[test]
+fn test_cancellation_of_tracked_transactions() {
+    let cancellation = Cancellation::new();
+    let mut in_flight = cancellation.track();
+    assert_eq!(cancellation.in_flight(), 1);
+    assert!(!in_flight.is_cancelled());
+
+    cancellation.cancel();
+    assert!(in_flight.is_cancelled());
+    // Transactions tracked after the request are not cancelled.
+    let later = cancellation.track();
+    assert!(!later.is_cancelled());
+    assert_eq!(cancellation.in_flight(), 2);
+
+    in_flight.settle();
+    in_flight.settle();
+    assert_eq!(cancellation.in_flight(), 1);
+    drop(later);
+    assert!(cancellation.wait(Duration::ZERO));
+}
+
+#[test]
+fn test_cancellation_wait_times_out() {
+    let cancellation = Cancellation::new();
+    let _in_flight = cancellation.track();
+    assert!(!cancellation.wait(Duration::from_millis(10)));
+}
+
+#[test]
+fn test_cancellation_transaction() {
+    let sender = Address::repeat_byte(0x11);
+    let tx: TypedTransaction = Eip1559TransactionRequest::new()
+        .chain_id(5)
+        .from(sender)
+        .to(Address::repeat_byte(0xff))
+        .data(vec![1, 2, 3])
+        .gas(30_000)
+        .nonce(7)
+        .max_fee_per_gas(gwei(20))
+        .max_priority_fee_per_gas(gwei(1))
+        .into();
+
+    let tx = BatcherTransaction::Calldata(tx);
+    let cancellation = TransactionManager::cancellation_transaction(&tx).unwrap();
+    let BatcherTransaction::Calldata(TypedTransaction::Eip1559(inner)) = cancellation
+    else {
+        panic!("expected an EIP-1559 cancellation");
+    };
+    assert_eq!(inner.to, Some(sender.into()));
+    assert_eq!(inner.value, Some(U256::zero()));
+    assert_eq!(inner.data, None);
+    assert_eq!(inner.gas, Some(U256::from(21_000)));
+    assert_eq!(inner.nonce, Some(U256::from(7)));
+    assert_eq!(inner.chain_id, Some(U64::from(5)));
+    assert_eq!(inner.max_fee_per_gas, Some(gwei(20)));
+}
+
+#[test]
+fn test_bump_blob_fee() {
+    let config = TransactionConfig::default();
+    assert_eq!(config.max_fee_per_blob_gas(U256::from(3)), U256::from(6));
+    // Blob replacements must double the max fee per blob gas.
+    assert_eq!(
+        config.bump_blob_fee(U256::from(6), U256::from(1)),
+        U256::from(12)
+    );
+    assert_eq!(
+        config.bump_blob_fee(U256::from(6), U256::from(10)),
+        U256::from(20)
+    );
+}
+
+#[tokio::test]
+async fn test_send_transaction_retries_flaky_queries_once_published() {
+    let mined = TransactionReceipt {
+        block_number: Some(U64::from(0x10)),
+        ..receipt(1, Address::repeat_byte(0xff))
+    };
+    // Receipts cannot be queried across two fee bump intervals, nor can fees
+    let devnet = common::devnet::Devnet::default()
+        .with_result(
+            "eth_sendRawTransaction",
+            json!(format!("{:?}", ethers_core::types::H256::repeat_byte(0xaa))),
+        )
+        .with_result("eth_blockNumber", json!("0x10"))
+        .with_result(
+            "eth_getTransactionReceipt",
+            serde_json::to_value(&mined).unwrap(),
+        )
+        .with_failures("eth_getTransactionReceipt", 10)
+        .with_failures("eth_getBlockByNumber", usize::MAX);
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let wallet = LocalWallet::from_str(
+        "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72",
+    )
+    .unwrap()
+    .with_chain_id(5u64);
+    let config = TransactionConfig {
+        fee_bump_interval: Duration::from_millis(40),
+        receipt_query_interval: Duration::from_millis(10),
+        num_confirmations: 1,
+        ..Default::default()
+    };
+    let tx: TypedTransaction = Eip1559TransactionRequest::new()
+        .chain_id(5)
+        .from(wallet.address())
+        .to(Address::repeat_byte(0xff))
+        .data(vec![1, 2, 3])
+        .gas(30_000)
+        .nonce(7)
+        .max_fee_per_gas(gwei(20))
+        .max_priority_fee_per_gas(gwei(1))
+        .into();
+
+    let receipt = TransactionManager::send_transaction(
+        provider,
+        wallet,
+        &config,
+        BatcherTransaction::Calldata(tx),
+        &mut Cancellation::new().track(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(receipt.block_number, mined.block_number);
+    assert_eq!(devnet.calls("eth_sendRawTransaction").len(), 1);
+    assert!(devnet.calls("eth_getTransactionReceipt").len() > 10);
+    assert!(!devnet.calls("eth_getBlockByNumber").is_empty());
+}
+
+#[tokio::test]
+async fn test_send_transaction_fails_when_nothing_is_published() {
+    let devnet = common::devnet::Devnet::default();
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let wallet = LocalWallet::from_str(
+        "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72",
+    )
+    .unwrap()
+    .with_chain_id(5u64);
+    let tx: TypedTransaction = Eip1559TransactionRequest::new()
+        .chain_id(5)
+        .from(wallet.address())
+        .to(Address::repeat_byte(0xff))
+        .gas(30_000)
+        .nonce(7)
+        .max_fee_per_gas(gwei(20))
+        .max_priority_fee_per_gas(gwei(1))
+        .into();
+
+    let result = TransactionManager::send_transaction(
+        provider,
+        wallet,
+        &TransactionConfig::default(),
+        BatcherTransaction::Calldata(tx),
+        &mut Cancellation::new().track(),
+    )
+    .await;
+    assert!(result.is_err());
+    assert!(devnet.calls("eth_getTransactionReceipt").is_empty());
+}
+
+/// Returns the nonce of the raw EIP-1559 transaction sent with the frame data byte.
+fn sent_nonce(devnet: &common::devnet::Devnet, byte: u8) -> Option<u64> {
+    devnet
+        .calls("eth_sendRawTransaction")
+        .iter()
+        .filter_map(|params| params[0].as_str())
+        .find(|raw| raw.contains(&format!("{:02x}", byte).repeat(32)))
+        .map(|raw| {
+            let raw = hex::decode(raw.trim_start_matches("0x")).unwrap();
+            Rlp::new(&raw[1..]).val_at(1).unwrap()
+        })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_failed_send_hands_its_nonce_back_while_later_frames_are_in_flight() {
+    let key = "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72";
+    let sender_address = LocalWallet::from_str(key).unwrap().address();
+    // The first frame's transaction is rejected, the others stay pending
+    let devnet = common::devnet::Devnet::default()
+        .with_result(
+            "eth_getBlockByNumber",
+            json!({ "number": "0x10", "baseFeePerGas": "0x9502f900" }),
+        )
+        .with_result("eth_maxPriorityFeePerGas", json!("0x3b9aca00"))
+        .with_result("eth_getTransactionCount", json!("0x7"))
+        .with_result(
+            "eth_sendRawTransaction",
+            json!(format!("{:?}", H256::repeat_byte(0xee))),
+        )
+        .with_result("eth_blockNumber", json!("0x10"))
+        .with_result("eth_getTransactionReceipt", Value::Null)
+        .with_failures_containing("eth_sendRawTransaction", &"aa".repeat(32))
+        .with_delay("eth_sendRawTransaction", Duration::from_millis(200));
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let nonce_manager = NonceManager::new();
+    let mut tx_manager = TransactionManager::new(
+        Some(5),
+        Some(Address::repeat_byte(0xff)),
+        Some(sender_address),
+        Some(key.to_string()),
+        provider,
+    );
+    tx_manager.with_transaction_config(TransactionConfig {
+        max_pending_transactions: 3,
+        receipt_query_interval: Duration::from_millis(10),
+        ..Default::default()
+    });
+    tx_manager.with_nonce_manager(nonce_manager.clone());
+    let (data_sender, data_receiver) = mpsc::channel();
+    let (outcome_sender, outcome_receiver) = mpsc::channel();
+    tx_manager.with_receiver(data_receiver);
+    tx_manager.with_sender(outcome_sender);
+    let _handle = tx_manager.spawn().unwrap();
+    let frame = |byte: u8| {
+        let id = TransactionID::new([byte; 16], 0);
+        (
+            id.clone(),
+            Box::pin(TaggedData::new(vec![byte; 32].into(), id)),
+        )
+    };
+
+    // Two frames are in flight when the first fails to send
+    let (first, tagged) = frame(0xaa);
+    data_sender.send(tagged).unwrap();
+    data_sender.send(frame(0xbb).1).unwrap();
+    let outcome = tokio::task::block_in_place(|| {
+        outcome_receiver.recv_timeout(Duration::from_secs(10))
+    })
+    .unwrap();
+    assert_eq!(*outcome, TransactionOutcome::Failed(first));
+
+    // The failed nonce fills the gap, without colliding with the frame in flight
+    data_sender.send(frame(0xcc).1).unwrap();
+    data_sender.send(frame(0xdd).1).unwrap();
+    let started = Instant::now();
+    while devnet.calls("eth_sendRawTransaction").len() < 4 {
+        assert!(started.elapsed() < Duration::from_secs(10));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(sent_nonce(&devnet, 0xaa), Some(7));
+    assert_eq!(sent_nonce(&devnet, 0xbb), Some(8));
+    assert_eq!(sent_nonce(&devnet, 0xcc), Some(7));
+    assert_eq!(sent_nonce(&devnet, 0xdd), Some(9));
+    assert_eq!(nonce_manager.in_flight(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auto_data_availability_switches_once_nothing_is_in_flight() {
+    let key = "4a6e5ceb37cd67ed8e740cc25b0ee6d11f6cfabe366daad1c908dec1d178bc72";
+    let sender_address = LocalWallet::from_str(key).unwrap().address();
+    // Blobs are expensive at first, so the first channel is posted as calldata
+    let devnet = common::devnet::Devnet::default()
+        .with_result(
+            "eth_getBlockByNumber",
+            json!({ "number": "0x10", "baseFeePerGas": "0x9502f900" }),
+        )
+        .with_result("eth_maxPriorityFeePerGas", json!("0x3b9aca00"))
+        .with_result("eth_blobBaseFee", json!("0x3b9aca00"))
+        .with_result("eth_getTransactionCount", json!("0x7"))
+        .with_result(
+            "eth_sendRawTransaction",
+            json!(format!("{:?}", H256::repeat_byte(0xee))),
+        )
+        .with_result("eth_blockNumber", json!("0x10"))
+        .with_result("eth_getTransactionReceipt", Value::Null);
+    let provider = Provider::<Http>::try_from(devnet.serve().await).unwrap();
+    let mut tx_manager = TransactionManager::new(
+        Some(5),
+        Some(Address::repeat_byte(0xff)),
+        Some(sender_address),
+        Some(key.to_string()),
+        provider,
+    );
+    tx_manager.with_kzg_committer(Arc::new(MockCommitter));
+    tx_manager.with_transaction_config(TransactionConfig {
+        max_pending_transactions: 2,
+        num_confirmations: 1,
+        receipt_query_interval: Duration::from_millis(10),
+        data_availability_type: DataAvailabilityType::Auto,
+        ..Default::default()
+    });
+    let (data_sender, data_receiver) = mpsc::channel();
+    let (outcome_sender, outcome_receiver) = mpsc::channel();
+    tx_manager.with_receiver(data_receiver);
+    tx_manager.with_sender(outcome_sender);
+    let _handle = tx_manager.spawn().unwrap();
+    let frame = |byte: u8| {
+        let id = TransactionID::new([byte; 16], 0);
+        (
+            id.clone(),
+            Box::pin(TaggedData::new(vec![byte; 32].into(), id)),
+        )
+    };
+    let wait_for_sends = |count: usize| {
+        let devnet = devnet.clone();
+        async move {
+            let started = Instant::now();
+            while devnet.calls("eth_sendRawTransaction").len() < count {
+                assert!(started.elapsed() < Duration::from_secs(10));
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+    };
+
+    let (first, tagged) = frame(0xaa);
+    data_sender.send(tagged).unwrap();
+    wait_for_sends(1).await;
+
+    // Blobs become cheaper, but the next channel waits for the calldata transaction in flight
+    let _ = devnet.clone().with_result("eth_blobBaseFee", json!("0x1"));
+    data_sender.send(frame(0xbb).1).unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(devnet.calls("eth_sendRawTransaction").len(), 1);
+    assert!(outcome_receiver.try_recv().is_err());
+
+    // Once the calldata transaction is mined, the next channel is posted as blobs
+    let mined = TransactionReceipt {
+        block_number: Some(U64::from(0x10)),
+        ..receipt(1, Address::repeat_byte(0xff))
+    };
+    let _ = devnet.clone().with_result(
+        "eth_getTransactionReceipt",
+        serde_json::to_value(&mined).unwrap(),
+    );
+    let outcome = tokio::task::block_in_place(|| {
+        outcome_receiver.recv_timeout(Duration::from_secs(10))
+    })
+    .unwrap();
+    assert!(matches!(&*outcome, TransactionOutcome::Confirmed(id, _) if *id == first));
+    wait_for_sends(2).await;
+    let sent = devnet.calls("eth_sendRawTransaction");
+    assert!(sent[0][0].as_str().unwrap().starts_with("0x02"));
+    assert!(sent[1][0].as_str().unwrap().starts_with("0x03"));
+}